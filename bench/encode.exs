@@ -0,0 +1,28 @@
+# Compares the parsing of a file with its decoding alone, to measure the encoding of the
+# records into terms.
+#
+#     mix run bench/encode.exs [filename] [copies]
+{filename, copies} =
+  case System.argv() do
+    [filename, copies] -> {filename, String.to_integer(copies)}
+    [filename] -> {filename, 50}
+    [] -> {"samples/marc8_multiple.mrc", 50}
+  end
+
+data = filename |> File.read!() |> List.duplicate(copies) |> IO.iodata_to_binary()
+runs = 10
+
+measure = fn nif ->
+  nif.()
+
+  {microseconds, _} = :timer.tc(fn -> for _ <- 1..runs, do: nif.() end)
+  microseconds / runs / 1000
+end
+
+decode = measure.(fn -> MarcRecord.decode_records_wrapper(data, []) end)
+parse = measure.(fn -> MarcRecord.parse_records_wrapper(data, []) end)
+
+IO.puts("#{filename} x #{copies} (#{byte_size(data)} bytes)")
+IO.puts("decoding:           #{Float.round(decode, 1)} ms")
+IO.puts("decoding, encoding: #{Float.round(parse, 1)} ms")
+IO.puts("encoding:           #{Float.round(parse - decode, 1)} ms")
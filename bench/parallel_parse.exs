@@ -0,0 +1,32 @@
+# Compares the sequential and parallel parsing of a file, repeated to make a larger input.
+#
+#     mix run bench/parallel_parse.exs [filename] [copies]
+{filename, copies} =
+  case System.argv() do
+    [filename, copies] -> {filename, String.to_integer(copies)}
+    [filename] -> {filename, 50}
+    [] -> {"samples/marc8_multiple.mrc", 50}
+  end
+
+data = filename |> File.read!() |> List.duplicate(copies) |> IO.iodata_to_binary()
+runs = 10
+
+measure = fn options ->
+  # A first run to warm up the thread pool.
+  MarcRecord.parse_records_wrapper(data, options)
+
+  {microseconds, _} =
+    :timer.tc(fn ->
+      for _ <- 1..runs, do: MarcRecord.parse_records_wrapper(data, options)
+    end)
+
+  microseconds / runs / 1000
+end
+
+sequential = measure.(parallel: false)
+parallel = measure.(parallel: true)
+
+IO.puts("#{filename} x #{copies} (#{byte_size(data)} bytes), #{System.schedulers_online()} cores")
+IO.puts("sequential: #{Float.round(sequential, 1)} ms")
+IO.puts("parallel:   #{Float.round(parallel, 1)} ms")
+IO.puts("speedup:    #{Float.round(sequential / parallel, 2)}x")
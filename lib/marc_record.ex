@@ -9,6 +9,36 @@ defmodule MarcRecord do
   use Rustler, otp_app: :marc_record_ex, crate: :marc_record_nif
 
   def parse_records_wrapper(_binary_data), do: error()
+  def parse_records_wrapper(_binary_data, _options), do: error()
+  def validate_records_wrapper(_binary_data), do: error()
+  def parse_records_with_warnings_wrapper(_binary_data), do: error()
+  def authority_records_wrapper(_binary_data), do: error()
+  def holdings_records_wrapper(_binary_data), do: error()
+  def unimarc_records_wrapper(_binary_data), do: error()
+  def unimarc_to_marc21_wrapper(_binary_data), do: error()
+  def marc21_to_unimarc_wrapper(_binary_data), do: error()
+  def to_dublin_core_wrapper(_binary_data), do: error()
+  def to_oai_dc_wrapper(_binary_data), do: error()
+  def to_mods_wrapper(_binary_data), do: error()
+  def to_bibframe_ntriples_wrapper(_binary_data), do: error()
+  def to_bibframe_turtle_wrapper(_binary_data), do: error()
+  def to_schema_org_wrapper(_binary_data), do: error()
+  def to_ris_wrapper(_binary_data), do: error()
+  def to_bibtex_wrapper(_binary_data), do: error()
+  def to_csl_json_wrapper(_binary_data), do: error()
+  def onix_to_marc_wrapper(_binary_data), do: error()
+  def summary_wrapper(_binary_data), do: error()
+  def standard_numbers_wrapper(_binary_data), do: error()
+  def match_keys_wrapper(_binary_data), do: error()
+  def dedupe_wrapper(_batches, _options), do: error()
+  def diff_records_wrapper(_a, _b), do: error()
+  def merge_records_wrapper(_master, _incoming, _options), do: error()
+  def apply_updates_wrapper(_base, _updates, _options), do: error()
+  def chunk_records_wrapper(_binary_data, _chunk_size), do: error()
+  def record_ranges_wrapper(_binary_data, _chunk_size), do: error()
+  def stats_wrapper(_binary_data, _options), do: error()
+  @doc false
+  def decode_records_wrapper(_binary_data, _options), do: error()
 
   defp error(), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -17,6 +47,142 @@ defmodule MarcRecord do
     MarcRecord.parse_records_wrapper(data)
   end
 
+  def parse_records(filename, options) do
+    data = File.read!(filename)
+    MarcRecord.parse_records_wrapper(data, options)
+  end
+
+  def validate_records(filename) do
+    data = File.read!(filename)
+    MarcRecord.validate_records_wrapper(data)
+  end
+
+  def parse_records_with_warnings(filename) do
+    data = File.read!(filename)
+    MarcRecord.parse_records_with_warnings_wrapper(data)
+  end
+
+  def authority_records(filename) do
+    data = File.read!(filename)
+    MarcRecord.authority_records_wrapper(data)
+  end
+
+  def holdings_records(filename) do
+    data = File.read!(filename)
+    MarcRecord.holdings_records_wrapper(data)
+  end
+
+  def unimarc_records(filename) do
+    data = File.read!(filename)
+    MarcRecord.unimarc_records_wrapper(data)
+  end
+
+  def unimarc_to_marc21(filename) do
+    data = File.read!(filename)
+    MarcRecord.unimarc_to_marc21_wrapper(data)
+  end
+
+  def marc21_to_unimarc(filename) do
+    data = File.read!(filename)
+    MarcRecord.marc21_to_unimarc_wrapper(data)
+  end
+
+  def to_dublin_core(filename, format \\ :map) do
+    data = File.read!(filename)
+
+    case format do
+      :map -> MarcRecord.to_dublin_core_wrapper(data)
+      :oai_dc -> MarcRecord.to_oai_dc_wrapper(data)
+    end
+  end
+
+  def to_mods(filename) do
+    data = File.read!(filename)
+    MarcRecord.to_mods_wrapper(data)
+  end
+
+  def to_bibframe(filename, format \\ :turtle) do
+    data = File.read!(filename)
+
+    case format do
+      :turtle -> MarcRecord.to_bibframe_turtle_wrapper(data)
+      :ntriples -> MarcRecord.to_bibframe_ntriples_wrapper(data)
+    end
+  end
+
+  def to_schema_org(filename) do
+    data = File.read!(filename)
+    MarcRecord.to_schema_org_wrapper(data)
+  end
+
+  def to_citation(filename, format) do
+    data = File.read!(filename)
+
+    case format do
+      :ris -> MarcRecord.to_ris_wrapper(data)
+      :bibtex -> MarcRecord.to_bibtex_wrapper(data)
+      :csl_json -> MarcRecord.to_csl_json_wrapper(data)
+    end
+  end
+
+  def onix_to_marc(filename) do
+    data = File.read!(filename)
+    MarcRecord.onix_to_marc_wrapper(data)
+  end
+
+  def summary(filename) do
+    data = File.read!(filename)
+    MarcRecord.summary_wrapper(data)
+  end
+
+  def standard_numbers(filename) do
+    data = File.read!(filename)
+    MarcRecord.standard_numbers_wrapper(data)
+  end
+
+  def match_keys(filename) do
+    data = File.read!(filename)
+    MarcRecord.match_keys_wrapper(data)
+  end
+
+  def dedupe(filenames, options \\ []) do
+    batches = Enum.map(filenames, &File.read!/1)
+    MarcRecord.dedupe_wrapper(batches, options)
+  end
+
+  def diff_records(a_filename, b_filename) do
+    a = File.read!(a_filename)
+    b = File.read!(b_filename)
+    MarcRecord.diff_records_wrapper(a, b)
+  end
+
+  def merge_records(master_filename, incoming_filename, options \\ []) do
+    master = File.read!(master_filename)
+    incoming = File.read!(incoming_filename)
+    MarcRecord.merge_records_wrapper(master, incoming, options)
+  end
+
+  def apply_updates(base_filename, updates_filename, options \\ []) do
+    base = File.read!(base_filename)
+    updates = File.read!(updates_filename)
+    MarcRecord.apply_updates_wrapper(base, updates, options)
+  end
+
+  def chunk_records(filename, chunk_size \\ 10_000) do
+    data = File.read!(filename)
+    MarcRecord.chunk_records_wrapper(data, chunk_size)
+  end
+
+  def record_ranges(filename, chunk_size \\ 1) do
+    data = File.read!(filename)
+    MarcRecord.record_ranges_wrapper(data, chunk_size)
+  end
+
+  def stats(filename, options \\ []) do
+    data = File.read!(filename)
+    MarcRecord.stats_wrapper(data, options)
+  end
+
   def example do
     parse_records("./samples/marc8_multiple.mrc")
   end
@@ -0,0 +1,120 @@
+//! Throughput of the decoding path on the sample files, to measure the changes made for
+//! performance: `cargo bench`, or `cargo bench -- decode_raw_records` for a single group.
+//!
+//! The encoding into terms is not covered: terms can only be built inside the BEAM, which
+//! provides the NIF API to the library when it loads it. `bench/encode.exs` measures it there.
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use marc_record::parse_records;
+use marc_record_nif::options::{Flavor, Normalization, ParseOptions};
+use marc_record_nif::raw::split_records;
+use marc_record_nif::{decode_raw_records, RecordWrapper};
+
+const SAMPLES: [&str; 2] = ["marc8_multiple.mrc", "more_difficult.mrc"];
+
+/// The records of a sample which can be decoded: a broken record would stop the decoding of
+/// the whole file.
+fn valid_records(name: &str) -> Vec<u8> {
+    let path = format!("{}/../../samples/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let data = std::fs::read(&path).unwrap_or_else(|error| panic!("{}: {}", path, error));
+    split_records(&data)
+        .iter()
+        .filter(|raw| raw.parse(Flavor::Marc21).is_ok())
+        .flat_map(|raw| raw.bytes.iter().copied())
+        .collect()
+}
+
+/// The decoding by `marc-record` alone, the baseline of the other group.
+fn bench_parse_records(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_records");
+    for name in SAMPLES {
+        let data = valid_records(name);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_function(name, |b| b.iter(|| parse_records(black_box(&data))));
+    }
+    group.finish();
+}
+
+/// The construction of the wrappers from the records decoded by `marc-record`, with the default
+/// options and with all of them. The decoding is done beforehand and not measured.
+fn bench_record_wrapper(c: &mut Criterion) {
+    let all_options = ParseOptions {
+        strip_punctuation: true,
+        normalization: Some(Normalization::SearchKey),
+        link_fields: true,
+        ..ParseOptions::default()
+    };
+    let mut group = c.benchmark_group("record_wrapper");
+    for name in SAMPLES {
+        let data = valid_records(name);
+        let leaders: Vec<&[u8]> = split_records(&data)
+            .iter()
+            .map(|raw| raw.leader().unwrap_or_default())
+            .collect();
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        for (label, options) in [
+            ("default", ParseOptions::default()),
+            ("all", all_options.clone()),
+        ] {
+            group.bench_function(format!("{}/{}", name, label), |b| {
+                b.iter_batched(
+                    || parse_records(&data).expect("the records are valid"),
+                    |records| {
+                        records
+                            .into_iter()
+                            .zip(&leaders)
+                            .map(|(record, leader)| RecordWrapper::new(record, leader, &options))
+                            .collect::<Vec<_>>()
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+/// The decoding done by the parse NIFs before encoding: splitting, leader masking, decoding,
+/// subfield positions and wrapping, with the default options and with all of them, each
+/// sequential and parallel.
+fn bench_decode_raw_records(c: &mut Criterion) {
+    let all_options = ParseOptions {
+        strip_punctuation: true,
+        normalization: Some(Normalization::SearchKey),
+        link_fields: true,
+        ..ParseOptions::default()
+    };
+    let mut group = c.benchmark_group("decode_raw_records");
+    for name in SAMPLES {
+        let data = valid_records(name);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        for (label, options) in [
+            ("default", ParseOptions::default()),
+            ("all", all_options.clone()),
+        ] {
+            for parallel in [false, true] {
+                let options = ParseOptions {
+                    parallel,
+                    ..options.clone()
+                };
+                let mode = if parallel { "parallel" } else { "sequential" };
+                group.bench_function(format!("{}/{}/{}", name, label, mode), |b| {
+                    b.iter(|| {
+                        decode_raw_records(black_box(&data), &options)
+                            .unwrap_or_else(|_| panic!("{} has invalid records", name))
+                    })
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parse_records,
+    bench_record_wrapper,
+    bench_decode_raw_records
+);
+criterion_main!(benches);
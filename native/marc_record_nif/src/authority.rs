@@ -0,0 +1,294 @@
+/// Support for the MARC 21 Authority format (leader/06 = `z`).
+///
+/// Authority records establish the authorized form of a heading (1XX) and link it to its
+/// variant forms, the see-from tracings (4XX), and to related headings, the see-also-from
+/// tracings (5XX). Their 008 field also follows a layout of its own.
+///
+/// Info about the format can be found here: https://www.loc.gov/marc/authority/
+use rustler::{Encoder, Env, Term};
+
+use crate::crosswalk;
+use crate::fixed_data::{Codes, FixedLengthData, Layout};
+use crate::{DataFieldWrapper, RecordWrapper};
+
+pub struct AuthorityRecord {
+    control_number: Option<String>,
+    fixed_length_data: Option<FixedLengthData>,
+    heading: Option<Heading>,
+    see_from: Vec<Heading>,
+    see_also: Vec<Heading>,
+}
+
+impl AuthorityRecord {
+    pub(crate) fn new(record: &RecordWrapper) -> Self {
+        let headings_in = |block: char| {
+            record
+                .data_fields()
+                .filter(move |field| field.tag.starts_with(block))
+                .map(Heading::new)
+        };
+        AuthorityRecord {
+            control_number: record.control_field("001").map(str::to_string),
+            fixed_length_data: record
+                .control_field("008")
+                .map(|data| FixedLengthData::new(data, AUTHORITY_008)),
+            heading: headings_in('1').next(),
+            see_from: headings_in('4').collect(),
+            see_also: headings_in('5').collect(),
+        }
+    }
+}
+
+impl Encoder for AuthorityRecord {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("control_number", self.control_number.encode(env)),
+                ("fixed_length_data", self.fixed_length_data.encode(env)),
+                ("heading", self.heading.encode(env)),
+                ("see_from", self.see_from.encode(env)),
+                ("see_also", self.see_also.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+/// A heading built from a 1XX, 4XX or 5XX field.
+pub struct Heading {
+    tag: String,
+    text: String,
+    relationship: Option<String>,
+}
+
+impl Heading {
+    fn new(field: &DataFieldWrapper) -> Self {
+        Heading {
+            tag: field.tag.clone(),
+            text: crosswalk::heading_text(field),
+            relationship: field.subfield("i").map(|data| data.trim().to_string()),
+        }
+    }
+}
+
+impl Encoder for Heading {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("tag", self.tag.encode(env)),
+                ("heading", self.text.encode(env)),
+                ("relationship", self.relationship.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+const APPROPRIATE: Codes = &[('a', "appropriate"), ('b', "not_appropriate")];
+
+/// Layout of the authority 008 field.
+const AUTHORITY_008: Layout = &[
+    ("date_entered_on_file", 0, 6, &[]),
+    (
+        "geographic_subdivision",
+        6,
+        1,
+        &[
+            (' ', "not_subdivided_geographically"),
+            ('d', "subdivided_geographically_direct"),
+            ('i', "subdivided_geographically_indirect"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    (
+        "romanization_scheme",
+        7,
+        1,
+        &[
+            ('a', "international_standard"),
+            ('b', "national_standard"),
+            ('c', "national_library_association_standard"),
+            ('d', "national_library_or_bibliographic_agency_standard"),
+            ('e', "local_standard"),
+            ('f', "standard_of_unknown_origin"),
+            ('g', "conventional_romanization"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    (
+        "language_of_catalog",
+        8,
+        1,
+        &[
+            (' ', "no_information_provided"),
+            ('b', "english_and_french"),
+            ('e', "english_only"),
+            ('f', "french_only"),
+        ],
+    ),
+    (
+        "kind_of_record",
+        9,
+        1,
+        &[
+            ('a', "established_heading"),
+            ('b', "untraced_reference"),
+            ('c', "traced_reference"),
+            ('d', "subdivision"),
+            ('e', "node_label"),
+            ('f', "established_heading_and_subdivision"),
+            ('g', "reference_and_subdivision"),
+        ],
+    ),
+    (
+        "descriptive_cataloging_rules",
+        10,
+        1,
+        &[
+            ('a', "earlier_rules"),
+            ('b', "aacr1"),
+            ('c', "aacr2"),
+            ('d', "aacr2_compatible_heading"),
+            ('n', "not_applicable"),
+            ('z', "other"),
+        ],
+    ),
+    (
+        "subject_heading_system",
+        11,
+        1,
+        &[
+            ('a', "library_of_congress_subject_headings"),
+            ('b', "lc_subject_headings_for_childrens_literature"),
+            ('c', "medical_subject_headings"),
+            ('d', "national_agricultural_library_subject_authority_file"),
+            ('k', "canadian_subject_headings"),
+            ('n', "not_applicable"),
+            ('r', "art_and_architecture_thesaurus"),
+            ('s', "sears_list_of_subject_headings"),
+            ('v', "repertoire_de_vedettes_matiere"),
+            ('z', "other"),
+        ],
+    ),
+    (
+        "type_of_series",
+        12,
+        1,
+        &[
+            ('a', "monographic_series"),
+            ('b', "multipart_item"),
+            ('c', "series_like_phrase"),
+            ('n', "not_applicable"),
+            ('z', "other"),
+        ],
+    ),
+    (
+        "numbered_or_unnumbered_series",
+        13,
+        1,
+        &[
+            ('a', "numbered"),
+            ('b', "unnumbered"),
+            ('c', "numbering_varies"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    ("heading_use_main_or_added_entry", 14, 1, APPROPRIATE),
+    ("heading_use_subject_added_entry", 15, 1, APPROPRIATE),
+    ("heading_use_series_added_entry", 16, 1, APPROPRIATE),
+    (
+        "type_of_subject_subdivision",
+        17,
+        1,
+        &[
+            ('a', "topical"),
+            ('b', "form"),
+            ('c', "chronological"),
+            ('d', "geographic"),
+            ('e', "language"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    (
+        "type_of_government_agency",
+        28,
+        1,
+        &[
+            (' ', "not_a_government_agency"),
+            ('a', "autonomous_or_semi_autonomous_component"),
+            ('c', "multilocal"),
+            ('f', "federal_or_national"),
+            ('i', "international_intergovernmental"),
+            ('l', "local"),
+            ('m', "multistate"),
+            ('o', "government_agency_type_undetermined"),
+            ('s', "state_provincial_territorial_dependent"),
+            ('u', "unknown"),
+            ('z', "other"),
+        ],
+    ),
+    (
+        "reference_evaluation",
+        29,
+        1,
+        &[
+            ('a', "tracings_consistent_with_heading"),
+            ('b', "tracings_not_necessarily_consistent_with_heading"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    (
+        "record_update_in_process",
+        31,
+        1,
+        &[
+            ('a', "record_can_be_used"),
+            ('b', "record_is_being_updated"),
+        ],
+    ),
+    (
+        "undifferentiated_personal_name",
+        32,
+        1,
+        &[
+            ('a', "differentiated_personal_name"),
+            ('b', "undifferentiated_personal_name"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    (
+        "level_of_establishment",
+        33,
+        1,
+        &[
+            ('a', "fully_established"),
+            ('b', "memorandum"),
+            ('c', "provisional"),
+            ('d', "preliminary"),
+            ('n', "not_applicable"),
+        ],
+    ),
+    (
+        "modified_record",
+        38,
+        1,
+        &[
+            (' ', "not_modified"),
+            ('s', "shortened"),
+            ('x', "missing_characters"),
+        ],
+    ),
+    (
+        "cataloging_source",
+        39,
+        1,
+        &[
+            (' ', "national_bibliographic_agency"),
+            ('c', "cooperative_cataloging_program"),
+            ('d', "other"),
+            ('u', "unknown"),
+        ],
+    ),
+];
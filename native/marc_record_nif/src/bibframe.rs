@@ -0,0 +1,434 @@
+/// Conversion of MARC 21 bibliographic records to BIBFRAME 2.0 RDF, serialized as N-Triples or
+/// Turtle. Each record becomes a Work, described by the access points and the subjects, an
+/// Instance of that Work, described by the transcribed data, and an Item for each location
+/// (852) or electronic location (856). The resources are named after the control number
+/// (001) under the base IRI used by the converter of the Library of Congress.
+///
+/// Info about the model can be found here: https://www.loc.gov/bibframe/docs/bibframe2-model.html
+use crate::crosswalk::{self, subfields_text};
+use crate::leader::{BibliographicalLevelWrapper, RecordTypeWrapper};
+use crate::{DataFieldWrapper, RecordWrapper};
+
+const BASE: &str = "http://example.org/";
+const BF: &str = "http://id.loc.gov/ontologies/bibframe/";
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const RDFS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+const RELATORS: &str = "http://id.loc.gov/vocabulary/relators/";
+const LANGUAGES: &str = "http://id.loc.gov/vocabulary/languages/";
+const ISSUANCE: &str = "http://id.loc.gov/vocabulary/issuance/";
+
+/// Prefixes used to shorten the IRIs in Turtle.
+const PREFIXES: [(&str, &str); 3] = [("bf", BF), ("rdf", RDF), ("rdfs", RDFS)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Iri(String),
+    /// A blank node, labeled after the record and its rank in the record so that the graphs of
+    /// several records can be merged.
+    Blank(String),
+    Literal(String),
+}
+
+pub struct Graph {
+    triples: Vec<(Node, String, Node)>,
+    blank_nodes: usize,
+    /// The number of the record in the binary, starting at 1.
+    number: usize,
+}
+
+impl Graph {
+    /// Builds the graph of a record. `position` names the resources of records without a
+    /// control number and the blank nodes.
+    pub(crate) fn new(record: &RecordWrapper, position: usize) -> Self {
+        let mut graph = Graph {
+            triples: Vec::new(),
+            blank_nodes: 0,
+            number: position + 1,
+        };
+        let id = record
+            .control_field("001")
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(encode_iri_component)
+            .unwrap_or_else(|| format!("record{}", graph.number));
+        let work = Node::Iri(format!("{}{}#Work", BASE, id));
+        let instance = Node::Iri(format!("{}{}#Instance", BASE, id));
+
+        graph.describe_work(record, &work);
+        graph.add(&work, &bf("hasInstance"), instance.clone());
+        graph.describe_instance(record, &instance);
+        graph.add(&instance, &bf("instanceOf"), work);
+
+        let locations = record
+            .data_fields()
+            .filter(|field| field.tag == "852" || field.tag == "856");
+        for (index, field) in locations.enumerate() {
+            let item = Node::Iri(format!("{}{}#Item{}", BASE, id, index + 1));
+            graph.add(&item, &rdf("type"), Node::Iri(bf("Item")));
+            graph.describe_item(field, &item);
+            graph.add(&item, &bf("itemOf"), instance.clone());
+        }
+        graph
+    }
+
+    fn add(&mut self, subject: &Node, predicate: &str, object: Node) {
+        self.triples
+            .push((subject.clone(), predicate.to_string(), object));
+    }
+
+    fn add_literal(&mut self, subject: &Node, predicate: &str, value: Option<String>) {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            self.add(subject, predicate, Node::Literal(value));
+        }
+    }
+
+    /// Adds a blank node of the given class linked from the subject, and returns it.
+    fn add_blank(&mut self, subject: &Node, predicate: &str, class: &str) -> Node {
+        self.blank_nodes += 1;
+        let node = Node::Blank(format!("r{}b{}", self.number, self.blank_nodes));
+        self.add(subject, predicate, node.clone());
+        self.add(&node, &rdf("type"), Node::Iri(bf(class)));
+        node
+    }
+
+    /// Adds a blank node of the given class with a label.
+    fn add_labeled(&mut self, subject: &Node, predicate: &str, class: &str, label: String) {
+        if label.is_empty() {
+            return;
+        }
+        let node = self.add_blank(subject, predicate, class);
+        self.add(&node, &rdfs("label"), Node::Literal(label));
+    }
+
+    fn describe_work(&mut self, record: &RecordWrapper, work: &Node) {
+        self.add(work, &rdf("type"), Node::Iri(bf("Work")));
+        if let Some(class) = work_class(record.leader.record_type()) {
+            self.add(work, &rdf("type"), Node::Iri(bf(class)));
+        }
+        let title = ["130", "240", "245"]
+            .iter()
+            .find_map(|tag| record.data_fields().find(|field| field.tag == *tag));
+        if let Some(title) = title {
+            self.add_title(work, title);
+        }
+        for field in record.data_fields() {
+            match field.tag.as_str() {
+                "100" | "110" | "111" | "700" | "710" | "711" => self.add_contribution(work, field),
+                "600" | "610" | "611" | "630" | "650" | "651" => {
+                    let class = match field.tag.as_str() {
+                        "600" => "Person",
+                        "610" => "Organization",
+                        "611" => "Meeting",
+                        "630" => "Work",
+                        "650" => "Topic",
+                        _ => "Place",
+                    };
+                    let heading = crosswalk::heading_text(field);
+                    self.add_labeled(work, &bf("subject"), class, heading);
+                }
+                "655" => {
+                    let genre = crosswalk::heading_text(field);
+                    self.add_labeled(work, &bf("genreForm"), "GenreForm", genre);
+                }
+                _ => {}
+            }
+        }
+        for language in crosswalk::languages(record) {
+            let language = Node::Iri(format!("{}{}", LANGUAGES, language));
+            self.add(work, &bf("language"), language);
+        }
+    }
+
+    fn add_title(&mut self, resource: &Node, field: &DataFieldWrapper) {
+        let title = self.add_blank(resource, &bf("title"), "Title");
+        let main_title = match field.tag.as_str() {
+            "245" => subfields_text(field, "a"),
+            _ => subfields_text(field, "adfklmnoprs"),
+        };
+        self.add_literal(&title, &bf("mainTitle"), main_title);
+        if field.tag == "245" {
+            self.add_literal(&title, &bf("subtitle"), subfields_text(field, "b"));
+            self.add_literal(&title, &bf("partNumber"), subfields_text(field, "n"));
+            self.add_literal(&title, &bf("partName"), subfields_text(field, "p"));
+        }
+    }
+
+    /// A contribution of an agent with its roles, from the relator codes of `$4`.
+    fn add_contribution(&mut self, work: &Node, field: &DataFieldWrapper) {
+        let contribution = self.add_blank(work, &bf("contribution"), "Contribution");
+        let (class, codes) = match &field.tag[1..] {
+            "00" => ("Person", "abcdq"),
+            "10" => ("Organization", "abcdn"),
+            _ => ("Meeting", "acdnq"),
+        };
+        let agent = subfields_text(field, codes).unwrap_or_default();
+        self.add_labeled(&contribution, &bf("agent"), class, agent);
+        for code in field
+            .subfields
+            .iter()
+            .filter(|subfield| subfield.tag == "4")
+            .map(|subfield| subfield.data.trim())
+            .filter(|code| !code.is_empty())
+        {
+            let role = Node::Iri(format!("{}{}", RELATORS, encode_iri_component(code)));
+            self.add(&contribution, &bf("role"), role);
+        }
+    }
+
+    fn describe_instance(&mut self, record: &RecordWrapper, instance: &Node) {
+        self.add(instance, &rdf("type"), Node::Iri(bf("Instance")));
+        if let Some(class) = instance_class(record) {
+            self.add(instance, &rdf("type"), Node::Iri(bf(class)));
+        }
+        let issuance = match record.leader.bibliographic_level() {
+            BibliographicalLevelWrapper::Monograph
+            | BibliographicalLevelWrapper::MonographicComponentPart
+            | BibliographicalLevelWrapper::Collection => Some("mono"),
+            BibliographicalLevelWrapper::Serial
+            | BibliographicalLevelWrapper::SerialComponentPart => Some("serl"),
+            BibliographicalLevelWrapper::IntegratingResource => Some("intg"),
+            _ => None,
+        };
+        if let Some(issuance) = issuance {
+            let issuance = Node::Iri(format!("{}{}", ISSUANCE, issuance));
+            self.add(instance, &bf("issuance"), issuance);
+        }
+        for field in record.data_fields() {
+            match field.tag.as_str() {
+                "245" => {
+                    self.add_title(instance, field);
+                    let responsibility = subfields_text(field, "c");
+                    self.add_literal(instance, &bf("responsibilityStatement"), responsibility);
+                }
+                "020" | "022" => {
+                    let class = if field.tag == "020" { "Isbn" } else { "Issn" };
+                    if let Some(value) = subfields_text(field, "a") {
+                        let identifier = self.add_blank(instance, &bf("identifiedBy"), class);
+                        self.add(&identifier, &rdf("value"), Node::Literal(value));
+                    }
+                }
+                "250" => {
+                    let edition = subfields_text(field, "ab");
+                    self.add_literal(instance, &bf("editionStatement"), edition);
+                }
+                "300" => {
+                    let extent = subfields_text(field, "a").unwrap_or_default();
+                    self.add_labeled(instance, &bf("extent"), "Extent", extent);
+                }
+                _ => {}
+            }
+        }
+        if let Some(publication) = crosswalk::publication_field(record) {
+            let activity = self.add_blank(instance, &bf("provisionActivity"), "Publication");
+            let place = subfields_text(publication, "a").unwrap_or_default();
+            self.add_labeled(&activity, &bf("place"), "Place", place);
+            let agent = subfields_text(publication, "b").unwrap_or_default();
+            self.add_labeled(&activity, &bf("agent"), "Agent", agent);
+            self.add_literal(&activity, &bf("date"), subfields_text(publication, "c"));
+        }
+    }
+
+    fn describe_item(&mut self, field: &DataFieldWrapper, item: &Node) {
+        if field.tag == "856" {
+            if let Some(url) = field.subfield("u").map(str::trim) {
+                self.add(item, &bf("electronicLocator"), Node::Iri(encode_iri(url)));
+            }
+            return;
+        }
+        let holder = subfields_text(field, "a").unwrap_or_default();
+        self.add_labeled(item, &bf("heldBy"), "Agent", holder);
+        let shelf_mark = subfields_text(field, "khim").unwrap_or_default();
+        self.add_labeled(item, &bf("shelfMark"), "ShelfMark", shelf_mark);
+    }
+
+    pub fn to_ntriples(&self) -> String {
+        self.triples
+            .iter()
+            .map(|(subject, predicate, object)| {
+                format!(
+                    "{} <{}> {} .\n",
+                    ntriples_node(subject),
+                    predicate,
+                    ntriples_node(object)
+                )
+            })
+            .collect()
+    }
+
+    /// The triples in Turtle, grouped by subject.
+    pub fn to_turtle(&self) -> String {
+        let mut turtle = PREFIXES
+            .iter()
+            .map(|(prefix, namespace)| format!("@prefix {}: <{}> .\n", prefix, namespace))
+            .collect::<String>();
+        let mut subjects: Vec<&Node> = Vec::new();
+        for (subject, _, _) in &self.triples {
+            if !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+        for subject in subjects {
+            let statements = self
+                .triples
+                .iter()
+                .filter(|(candidate, _, _)| candidate == subject)
+                .map(|(_, predicate, object)| {
+                    let predicate = if predicate == &rdf("type") {
+                        "a".to_string()
+                    } else {
+                        turtle_iri(predicate)
+                    };
+                    format!("{} {}", predicate, turtle_node(object))
+                })
+                .collect::<Vec<_>>();
+            turtle.push_str(&format!(
+                "\n{}\n    {} .\n",
+                turtle_node(subject),
+                statements.join(" ;\n    ")
+            ));
+        }
+        turtle
+    }
+}
+
+fn bf(name: &str) -> String {
+    format!("{}{}", BF, name)
+}
+
+fn rdf(name: &str) -> String {
+    format!("{}{}", RDF, name)
+}
+
+fn rdfs(name: &str) -> String {
+    format!("{}{}", RDFS, name)
+}
+
+/// The subclass of Work from leader/06.
+fn work_class(record_type: &RecordTypeWrapper) -> Option<&'static str> {
+    let class = match record_type {
+        RecordTypeWrapper::LanguageMaterial | RecordTypeWrapper::ManuscriptLanguageMaterial => {
+            "Text"
+        }
+        RecordTypeWrapper::NotatedMusic | RecordTypeWrapper::ManuscriptNotatedMusic => {
+            "NotatedMusic"
+        }
+        RecordTypeWrapper::CartographicMaterial
+        | RecordTypeWrapper::ManuscriptCartographicMaterial => "Cartography",
+        RecordTypeWrapper::ProjectedMedium => "MovingImage",
+        RecordTypeWrapper::NonmusicalSoundRecording => "Audio",
+        RecordTypeWrapper::MusicalSoundRecording => "Audio",
+        RecordTypeWrapper::TwoDimensionalNonprojectableGraphic => "StillImage",
+        RecordTypeWrapper::ComputerFile | RecordTypeWrapper::Multimedia => "Multimedia",
+        RecordTypeWrapper::Kit | RecordTypeWrapper::MixedMaterials => "MixedMaterial",
+        RecordTypeWrapper::ThreeDimensionalArtifact => "Object",
+        _ => return None,
+    };
+    Some(class)
+}
+
+/// The subclass of Instance: manuscripts from leader/06, archival material from leader/08,
+/// electronic resources from the form of item of 008 (position 23 for most types of material,
+/// 29 for maps and visual materials), print otherwise for textual material.
+fn instance_class(record: &RecordWrapper) -> Option<&'static str> {
+    let record_type = record.leader.record_type();
+    if matches!(
+        record_type,
+        RecordTypeWrapper::ManuscriptLanguageMaterial
+            | RecordTypeWrapper::ManuscriptNotatedMusic
+            | RecordTypeWrapper::ManuscriptCartographicMaterial
+    ) {
+        return Some("Manuscript");
+    }
+    if record.leader.is_archival() {
+        return Some("Archival");
+    }
+    let form_position = match record_type {
+        RecordTypeWrapper::CartographicMaterial
+        | RecordTypeWrapper::ProjectedMedium
+        | RecordTypeWrapper::TwoDimensionalNonprojectableGraphic
+        | RecordTypeWrapper::ThreeDimensionalArtifact
+        | RecordTypeWrapper::Kit => 29,
+        _ => 23,
+    };
+    let form = record
+        .control_field("008")
+        .and_then(|data| data.chars().nth(form_position));
+    match (form, record_type) {
+        (Some('o' | 's' | 'q'), _) => Some("Electronic"),
+        (Some('f'), _) => Some("Tactile"),
+        (_, RecordTypeWrapper::LanguageMaterial | RecordTypeWrapper::NotatedMusic) => Some("Print"),
+        _ => None,
+    }
+}
+
+/// Percent-encodes the characters of an identifier which cannot appear in an IRI as is.
+fn encode_iri_component(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Percent-encodes the characters which are not allowed in an IRI, such as spaces in URLs.
+fn encode_iri(iri: &str) -> String {
+    let mut encoded = String::with_capacity(iri.len());
+    for c in iri.chars() {
+        if c.is_control() || " <>\"{}|\\^`".contains(c) {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+/// Escapes a literal for N-Triples and Turtle, which share the same string syntax.
+fn escape_literal(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn ntriples_node(node: &Node) -> String {
+    match node {
+        Node::Iri(iri) => format!("<{}>", iri),
+        Node::Blank(label) => format!("_:{}", label),
+        Node::Literal(value) => format!("\"{}\"", escape_literal(value)),
+    }
+}
+
+fn turtle_node(node: &Node) -> String {
+    match node {
+        Node::Iri(iri) => turtle_iri(iri),
+        _ => ntriples_node(node),
+    }
+}
+
+/// Shortens an IRI with a prefix when its local name is a plain name.
+fn turtle_iri(iri: &str) -> String {
+    PREFIXES
+        .iter()
+        .find_map(|(prefix, namespace)| {
+            let local = iri.strip_prefix(namespace)?;
+            let is_name =
+                !local.is_empty() && local.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            is_name.then(|| format!("{}:{}", prefix, local))
+        })
+        .unwrap_or_else(|| format!("<{}>", iri))
+}
@@ -0,0 +1,390 @@
+/// Conversion of MARC 21 bibliographic records to the citation formats read by reference
+/// managers such as Zotero: RIS, BibTeX and CSL-JSON. The record is first reduced to a
+/// `Citation`, whose item type comes from leader/06 and /07, then written in each format.
+///
+/// Info about the formats can be found here:
+/// - https://en.wikipedia.org/wiki/RIS_(file_format)
+/// - https://www.bibtex.org/Format/
+/// - https://citeproc-js.readthedocs.io/en/latest/csl-json/markup.html
+use crate::crosswalk::{self, subfields_text, Json};
+use crate::leader::{BibliographicalLevelWrapper, RecordTypeWrapper};
+use crate::punctuation;
+use crate::{DataFieldWrapper, RecordWrapper};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemType {
+    Book,
+    Chapter,
+    Article,
+    Journal,
+    Map,
+    Music,
+    Sound,
+    Video,
+    Software,
+    Document,
+}
+
+impl ItemType {
+    fn new(record: &RecordWrapper) -> Self {
+        match record.leader.bibliographic_level() {
+            BibliographicalLevelWrapper::MonographicComponentPart => return ItemType::Chapter,
+            BibliographicalLevelWrapper::SerialComponentPart => return ItemType::Article,
+            BibliographicalLevelWrapper::Serial => return ItemType::Journal,
+            _ => {}
+        }
+        match record.leader.record_type() {
+            RecordTypeWrapper::LanguageMaterial | RecordTypeWrapper::ManuscriptLanguageMaterial => {
+                ItemType::Book
+            }
+            RecordTypeWrapper::CartographicMaterial
+            | RecordTypeWrapper::ManuscriptCartographicMaterial => ItemType::Map,
+            RecordTypeWrapper::NotatedMusic | RecordTypeWrapper::ManuscriptNotatedMusic => {
+                ItemType::Music
+            }
+            RecordTypeWrapper::NonmusicalSoundRecording
+            | RecordTypeWrapper::MusicalSoundRecording => ItemType::Sound,
+            RecordTypeWrapper::ProjectedMedium => ItemType::Video,
+            RecordTypeWrapper::ComputerFile => ItemType::Software,
+            _ => ItemType::Document,
+        }
+    }
+
+    fn ris(self) -> &'static str {
+        match self {
+            ItemType::Book => "BOOK",
+            ItemType::Chapter => "CHAP",
+            ItemType::Article => "JOUR",
+            ItemType::Journal => "JFULL",
+            ItemType::Map => "MAP",
+            ItemType::Music => "MUSIC",
+            ItemType::Sound => "SOUND",
+            ItemType::Video => "VIDEO",
+            ItemType::Software => "COMP",
+            ItemType::Document => "GEN",
+        }
+    }
+
+    fn bibtex(self) -> &'static str {
+        match self {
+            ItemType::Book => "book",
+            ItemType::Chapter => "incollection",
+            ItemType::Article => "article",
+            _ => "misc",
+        }
+    }
+
+    fn csl(self) -> &'static str {
+        match self {
+            ItemType::Book => "book",
+            ItemType::Chapter => "chapter",
+            ItemType::Article => "article-journal",
+            ItemType::Journal => "periodical",
+            ItemType::Map => "map",
+            ItemType::Music => "musical_score",
+            ItemType::Sound => "song",
+            ItemType::Video => "motion_picture",
+            ItemType::Software => "software",
+            ItemType::Document => "document",
+        }
+    }
+}
+
+/// A person or an organization. Personal names are inverted in MARC 21, e.g. `Hugo, Victor`.
+struct Name {
+    family: String,
+    given: Option<String>,
+    is_personal: bool,
+}
+
+impl Name {
+    fn new(field: &DataFieldWrapper) -> Option<Self> {
+        let is_personal = field.tag.ends_with("00");
+        if !is_personal {
+            return Some(Name {
+                family: subfields_text(field, "abcdn")?,
+                given: None,
+                is_personal,
+            });
+        }
+        let name = subfields_text(field, "a")?;
+        let (family, given) = match name.split_once(',') {
+            Some((family, given)) => (family.trim(), Some(given.trim().to_string())),
+            None => (name.as_str(), None),
+        };
+        Some(Name {
+            family: family.to_string(),
+            given: given.filter(|given| !given.is_empty()),
+            is_personal,
+        })
+    }
+
+    fn inverted(&self) -> String {
+        match &self.given {
+            Some(given) => format!("{}, {}", self.family, given),
+            None => self.family.clone(),
+        }
+    }
+
+    fn to_csl(&self) -> Json {
+        if !self.is_personal {
+            return Json::object(vec![("literal", Some(Json::string(&self.family)))]);
+        }
+        Json::object(vec![
+            ("family", Some(Json::string(&self.family))),
+            ("given", self.given.as_deref().map(Json::string)),
+        ])
+    }
+}
+
+/// Editors are told apart from authors by the relator code (`$4`) or term (`$e`).
+fn is_editor(field: &DataFieldWrapper) -> bool {
+    field
+        .subfields
+        .iter()
+        .any(|subfield| match subfield.tag.as_str() {
+            "4" => subfield.data.trim() == "edt",
+            "e" => {
+                let term = subfield.data.trim().to_lowercase();
+                term.starts_with("ed") || term.starts_with("éd")
+            }
+            _ => false,
+        })
+}
+
+pub(crate) struct Citation {
+    id: String,
+    /// The BibTeX key, made of the characters of the id allowed in keys.
+    key: String,
+    item_type: ItemType,
+    title: Option<String>,
+    authors: Vec<Name>,
+    editors: Vec<Name>,
+    container_title: Option<String>,
+    year: Option<String>,
+    publisher: Option<String>,
+    place: Option<String>,
+    edition: Option<String>,
+    isbn: Vec<String>,
+    issn: Vec<String>,
+    url: Vec<String>,
+    abstract_note: Option<String>,
+    keywords: Vec<String>,
+    language: Option<String>,
+}
+
+impl Citation {
+    /// `position` identifies the records without a control number.
+    pub(crate) fn new(record: &RecordWrapper, position: usize) -> Self {
+        let field = |tag: &str| record.data_fields().find(|field| field.tag == tag);
+        let names = record.data_fields().filter(|field| {
+            ["100", "110", "111", "700", "710", "711"].contains(&field.tag.as_str())
+        });
+        let (editors, authors): (Vec<_>, Vec<_>) = names.partition(|field| is_editor(field));
+        let values = |tag: &str, code: &str| {
+            record
+                .data_fields()
+                .filter(|field| field.tag == tag)
+                .filter_map(|field| field.subfield(code))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        // The standard numbers can be followed by a qualifier, e.g. `2070360024 (br.)`.
+        let numbers = |tag: &str| {
+            values(tag, "a")
+                .iter()
+                .filter_map(|value| value.split_whitespace().next())
+                .map(|value| punctuation::strip(value, false).to_string())
+                .collect::<Vec<_>>()
+        };
+        let publication = crosswalk::publication_field(record);
+        let fallback_id = || format!("record{}", position + 1);
+        let id = record
+            .control_field("001")
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(fallback_id);
+        Citation {
+            key: Some(bibtex_key(&id))
+                .filter(|key| !key.is_empty())
+                .unwrap_or_else(fallback_id),
+            id,
+            item_type: ItemType::new(record),
+            title: field("245").and_then(|title| subfields_text(title, "abnp")),
+            authors: authors.into_iter().filter_map(Name::new).collect(),
+            editors: editors.into_iter().filter_map(Name::new).collect(),
+            // The host item entry links a component part to the book or serial holding it.
+            container_title: field("773").and_then(|host| subfields_text(host, "t")),
+            year: crosswalk::publication_year(record),
+            publisher: publication.and_then(|field| subfields_text(field, "b")),
+            place: publication.and_then(|field| subfields_text(field, "a")),
+            edition: field("250").and_then(|edition| subfields_text(edition, "a")),
+            isbn: numbers("020"),
+            issn: numbers("022"),
+            url: values("856", "u"),
+            abstract_note: field("520").and_then(|summary| subfields_text(summary, "a")),
+            keywords: record
+                .data_fields()
+                .filter(|field| field.tag.starts_with('6') && field.tag != "655")
+                .map(crosswalk::heading_text)
+                .filter(|keyword| !keyword.is_empty())
+                .collect(),
+            language: crosswalk::languages(record).into_iter().next(),
+        }
+    }
+
+    pub(crate) fn to_ris(&self) -> String {
+        let mut lines = vec![("TY", self.item_type.ris().to_string())];
+        lines.extend(self.authors.iter().map(|name| ("AU", name.inverted())));
+        lines.extend(self.editors.iter().map(|name| ("ED", name.inverted())));
+        let single = [
+            ("TI", &self.title),
+            ("T2", &self.container_title),
+            ("PY", &self.year),
+            ("PB", &self.publisher),
+            ("CY", &self.place),
+            ("ET", &self.edition),
+            ("AB", &self.abstract_note),
+            ("LA", &self.language),
+        ];
+        lines.extend(
+            single
+                .iter()
+                .filter_map(|(tag, value)| Some((*tag, value.as_ref()?.clone()))),
+        );
+        // RIS has a single tag for both ISBN and ISSN.
+        lines.extend(
+            self.isbn
+                .iter()
+                .chain(&self.issn)
+                .map(|sn| ("SN", sn.clone())),
+        );
+        lines.extend(self.keywords.iter().map(|keyword| ("KW", keyword.clone())));
+        lines.extend(self.url.iter().map(|url| ("UR", url.clone())));
+        lines.push(("ID", self.id.clone()));
+        lines.push(("ER", String::new()));
+        lines
+            .iter()
+            .map(|(tag, value)| format!("{}  - {}\n", tag, value.replace('\n', " ")))
+            .collect()
+    }
+
+    pub(crate) fn to_bibtex(&self) -> String {
+        let names = |names: &[Name]| {
+            Some(
+                names
+                    .iter()
+                    .map(|name| match name.is_personal {
+                        true => escape_bibtex(&name.inverted()),
+                        // Braces keep corporate names from being split into first and last names.
+                        false => format!("{{{}}}", escape_bibtex(&name.family)),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+            )
+            .filter(|names| !names.is_empty())
+        };
+        let container_field = match self.item_type {
+            ItemType::Article => "journal",
+            _ => "booktitle",
+        };
+        let fields = [
+            ("author", names(&self.authors)),
+            ("editor", names(&self.editors)),
+            ("title", self.title.clone()),
+            (container_field, self.container_title.clone()),
+            ("year", self.year.clone()),
+            ("publisher", self.publisher.clone()),
+            ("address", self.place.clone()),
+            ("edition", self.edition.clone()),
+            (
+                "isbn",
+                Some(self.isbn.join(", ")).filter(|isbn| !isbn.is_empty()),
+            ),
+            (
+                "issn",
+                Some(self.issn.join(", ")).filter(|issn| !issn.is_empty()),
+            ),
+            ("url", self.url.first().cloned()),
+            ("abstract", self.abstract_note.clone()),
+            (
+                "keywords",
+                Some(self.keywords.join(", ")).filter(|k| !k.is_empty()),
+            ),
+            ("language", self.language.clone()),
+        ];
+        let body = fields
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.as_ref()?;
+                // Names were escaped while joining them, corporate names being braced.
+                let value = match *name {
+                    "author" | "editor" => value.clone(),
+                    _ => escape_bibtex(value),
+                };
+                Some(format!("  {} = {{{}}}", name, value))
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+        format!(
+            "@{}{{{},\n{}\n}}\n",
+            self.item_type.bibtex(),
+            self.key,
+            body
+        )
+    }
+
+    pub(crate) fn to_csl_json(&self) -> String {
+        let names = |names: &[Name]| Some(Json::Array(names.iter().map(Name::to_csl).collect()));
+        let strings = |values: &[String]| Some(values.join(", ")).filter(|v| !v.is_empty());
+        let issued = self.year.as_ref().and_then(|year| {
+            let year = year.parse().ok()?;
+            Some(Json::object(vec![(
+                "date-parts",
+                Some(Json::Array(vec![Json::Array(vec![Json::Number(year)])])),
+            )]))
+        });
+        Json::object(vec![
+            ("id", Some(Json::string(&self.id))),
+            ("type", Some(Json::string(self.item_type.csl()))),
+            ("title", self.title.as_deref().map(Json::string)),
+            ("author", names(&self.authors)),
+            ("editor", names(&self.editors)),
+            (
+                "container-title",
+                self.container_title.as_deref().map(Json::string),
+            ),
+            ("issued", issued),
+            ("publisher", self.publisher.as_deref().map(Json::string)),
+            ("publisher-place", self.place.as_deref().map(Json::string)),
+            ("edition", self.edition.as_deref().map(Json::string)),
+            ("ISBN", strings(&self.isbn).map(Json::string)),
+            ("ISSN", strings(&self.issn).map(Json::string)),
+            ("URL", self.url.first().map(Json::string)),
+            ("abstract", self.abstract_note.as_deref().map(Json::string)),
+            ("keyword", strings(&self.keywords).map(Json::string)),
+            ("language", self.language.as_deref().map(Json::string)),
+        ])
+        .to_pretty_string()
+    }
+}
+
+/// Citation keys are limited to letters, digits and a few punctuation characters.
+fn bibtex_key(id: &str) -> String {
+    id.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || "-_:.".contains(*c))
+        .collect()
+}
+
+fn escape_bibtex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "&%$#_{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
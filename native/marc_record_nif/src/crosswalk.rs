@@ -0,0 +1,294 @@
+/// Helpers shared by the conversions of MARC 21 records to other metadata formats.
+use crate::punctuation;
+use crate::{DataFieldWrapper, RecordWrapper};
+
+/// The data of the subfields with the given codes, in the order of the field and separated by
+/// spaces, without the trailing punctuation, removed like the `strip_punctuation` option does.
+/// `None` when no such subfield has data.
+pub(crate) fn subfields_text(field: &DataFieldWrapper, codes: &str) -> Option<String> {
+    let text = field
+        .subfields
+        .iter()
+        .filter(|subfield| codes.contains(subfield.tag.as_str()))
+        .map(|subfield| subfield.data.trim())
+        .filter(|data| !data.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let keep_period = punctuation::keeps_period(&field.tag);
+    Some(punctuation::strip(&text, keep_period).to_string()).filter(|text| !text.is_empty())
+}
+
+/// The text of a heading. Subdivisions (`$v`, `$x`, `$y` and `$z`) are separated by `--`,
+/// control subfields and relators (`$e`, `$4`) are left out.
+pub(crate) fn heading_text(field: &DataFieldWrapper) -> String {
+    let mut text = String::new();
+    for subfield in &field.subfields {
+        let code = subfield.tag.as_str();
+        let data = subfield.data.trim();
+        if data.is_empty() || is_control_subfield(code) {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str(if "vxyz".contains(code) { "--" } else { " " });
+        }
+        text.push_str(data);
+    }
+    punctuation::strip(&text, false).to_string()
+}
+
+fn is_control_subfield(code: &str) -> bool {
+    code == "e" || code == "i" || code == "w" || code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// The language of the item (008/35-37) followed by the other codes of 041 `$a`.
+pub(crate) fn languages(record: &RecordWrapper) -> Vec<String> {
+    let mut languages = Vec::new();
+    let fixed = record
+        .control_field("008")
+        .map(|data| data.chars().skip(35).take(3).collect::<String>());
+    let coded = record
+        .data_fields()
+        .filter(|field| field.tag == "041")
+        .flat_map(|field| &field.subfields)
+        .filter(|subfield| subfield.tag == "a")
+        .flat_map(|subfield| {
+            // Codes were once recorded end to end in a single subfield.
+            let chars = subfield.data.trim().chars().collect::<Vec<_>>();
+            chars
+                .chunks(3)
+                .map(|code| code.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        });
+    for code in fixed.into_iter().chain(coded) {
+        let is_code = code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic());
+        if is_code && !languages.contains(&code) {
+            languages.push(code);
+        }
+    }
+    languages
+}
+
+/// The publication, distribution, etc. field: 260, or else the 264 of the publication.
+pub(crate) fn publication_field(record: &RecordWrapper) -> Option<&DataFieldWrapper> {
+    record
+        .data_fields()
+        .find(|field| field.tag == "260")
+        .or_else(|| {
+            record
+                .data_fields()
+                .find(|field| field.tag == "264" && field.indicator.chars().nth(1) == Some('1'))
+        })
+}
+
+/// The date of publication from 260/264 `$c`, or else from 008/07-10.
+pub(crate) fn publication_date(record: &RecordWrapper) -> Option<String> {
+    publication_field(record)
+        .and_then(|field| subfields_text(field, "c"))
+        .or_else(|| {
+            let date = record
+                .control_field("008")?
+                .chars()
+                .skip(7)
+                .take(4)
+                .collect::<String>();
+            Some(date).filter(|date| date.len() == 4 && date.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Escapes the characters which cannot appear as is in XML text and attribute values.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes indented XML elements, escaping their text and attribute values.
+pub(crate) struct XmlWriter {
+    xml: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    pub fn new() -> Self {
+        XmlWriter {
+            xml: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+            depth: 0,
+        }
+    }
+
+    pub fn start(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.open_tag(name, attributes);
+        self.xml.push_str(">\n");
+        self.depth += 1;
+    }
+
+    pub fn end(&mut self, name: &str) {
+        self.depth -= 1;
+        self.indent();
+        self.xml.push_str(&format!("</{}>\n", name));
+    }
+
+    /// An element holding only text. Nothing is written when the text is empty.
+    pub fn element(&mut self, name: &str, attributes: &[(&str, &str)], text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.open_tag(name, attributes);
+        self.xml
+            .push_str(&format!(">{}</{}>\n", escape_xml(text), name));
+    }
+
+    pub fn finish(self) -> String {
+        self.xml
+    }
+
+    fn open_tag(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.indent();
+        self.xml.push('<');
+        self.xml.push_str(name);
+        for (attribute, value) in attributes {
+            self.xml
+                .push_str(&format!(" {}=\"{}\"", attribute, escape_xml(value)));
+        }
+    }
+
+    fn indent(&mut self) {
+        self.xml.push_str(&"  ".repeat(self.depth));
+    }
+}
+
+/// A JSON value, enough for the documents written by the crosswalks. The members of objects
+/// keep their order.
+pub(crate) enum Json {
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    pub fn string(text: impl Into<String>) -> Self {
+        Json::String(text.into())
+    }
+
+    /// An object without the members which are missing or empty arrays.
+    pub fn object(members: Vec<(&'static str, Option<Json>)>) -> Self {
+        Json::Object(
+            members
+                .into_iter()
+                .filter_map(|(name, value)| Some((name, value?)))
+                .filter(|(_, value)| !value.is_empty())
+                .collect(),
+        )
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, Json::Array(values) if values.is_empty())
+    }
+
+    /// The value on several lines, indented by two spaces.
+    pub fn to_pretty_string(&self) -> String {
+        let mut json = String::new();
+        self.write(&mut json, 0);
+        json
+    }
+
+    fn write(&self, json: &mut String, depth: usize) {
+        let indent = |depth: usize| "  ".repeat(depth);
+        match self {
+            Json::Number(number) => json.push_str(&number.to_string()),
+            Json::String(text) => write_json_string(json, text),
+            Json::Array(values) if values.is_empty() => json.push_str("[]"),
+            Json::Array(values) => {
+                json.push_str("[\n");
+                for (index, value) in values.iter().enumerate() {
+                    json.push_str(&indent(depth + 1));
+                    value.write(json, depth + 1);
+                    json.push_str(if index + 1 < values.len() {
+                        ",\n"
+                    } else {
+                        "\n"
+                    });
+                }
+                json.push_str(&indent(depth));
+                json.push(']');
+            }
+            Json::Object(members) if members.is_empty() => json.push_str("{}"),
+            Json::Object(members) => {
+                json.push_str("{\n");
+                for (index, (name, value)) in members.iter().enumerate() {
+                    json.push_str(&indent(depth + 1));
+                    write_json_string(json, name);
+                    json.push_str(": ");
+                    value.write(json, depth + 1);
+                    json.push_str(if index + 1 < members.len() {
+                        ",\n"
+                    } else {
+                        "\n"
+                    });
+                }
+                json.push_str(&indent(depth));
+                json.push('}');
+            }
+        }
+    }
+}
+
+fn write_json_string(json: &mut String, text: &str) {
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            // Keeps the document safe to embed in a `<script>` element of an HTML page.
+            '<' => json.push_str("\\u003c"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// The first four digit year of the date of 260/264 `$c`, such as `c2011` or `[1998?]`, or
+/// else of 008/07-10, like `publication_date`.
+pub(crate) fn publication_year(record: &RecordWrapper) -> Option<String> {
+    let transcribed = publication_field(record).and_then(|field| field.subfield("c"));
+    let fixed = record
+        .control_field("008")
+        .map(|data| data.chars().skip(7).take(4).collect::<String>());
+    transcribed
+        .map(str::to_string)
+        .into_iter()
+        .chain(fixed)
+        .find_map(|date| {
+            let chars = date.chars().collect::<Vec<_>>();
+            chars
+                .windows(4)
+                .find(|window| window.iter().all(|c| c.is_ascii_digit()))
+                .map(|window| window.iter().collect::<String>())
+        })
+}
+
+/// The form of item (008/23) or the category of material (007/00) tells about electronic
+/// resources.
+pub(crate) fn is_electronic(record: &RecordWrapper) -> bool {
+    let form = record
+        .control_field("008")
+        .and_then(|data| data.chars().nth(23));
+    let category = record
+        .control_field("007")
+        .and_then(|data| data.chars().next());
+    matches!(form, Some('o' | 'q' | 's')) || category == Some('c')
+}
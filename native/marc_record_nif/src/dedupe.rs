@@ -0,0 +1,382 @@
+/// Detection of the records describing the same resource, across the files of several vendors.
+/// Each record gets match keys: its valid ISBNs, its OCLC numbers and normalized title and
+/// author keys. Records sharing a key according to the chosen strategies are grouped into
+/// clusters. Each matching pair of records gets the confidence of its best strategy, and a
+/// cluster the confidence of its weakest pair.
+use std::collections::{BTreeSet, HashMap};
+
+use rustler::{Encoder, Env, Term};
+
+use crate::normalization::normalize;
+use crate::options::{DedupeOptions, Normalization, Strategy};
+use crate::standard_number;
+use crate::RecordWrapper;
+
+/// The confidence of a match on an OCLC number, an ISBN, or the title and author. Titles which
+/// are similar but not equal lower the confidence further.
+const OCLC_CONFIDENCE: f64 = 1.0;
+const ISBN_CONFIDENCE: f64 = 0.95;
+const TITLE_AUTHOR_CONFIDENCE: f64 = 0.8;
+
+/// Prefixes of the OCLC numbers in 035 `$a`, e.g. `(OCoLC)ocm00012345`.
+const OCLC_PREFIXES: [&str; 3] = ["ocm", "ocn", "on"];
+
+pub struct MatchKeys {
+    pub control_number: Option<String>,
+    /// The valid ISBNs of `$a`, as ISBN-13.
+    pub isbns: Vec<String>,
+    /// The OCLC numbers without prefix and leading zeros.
+    pub oclc_numbers: Vec<String>,
+    /// The title proper without diacritics, case, punctuation and nonfiling characters.
+    pub title: Option<String>,
+    /// The surname of a person or the name of an organization, normalized like the title.
+    pub author: Option<String>,
+    pub year: Option<String>,
+}
+
+impl MatchKeys {
+    pub(crate) fn new(record: &RecordWrapper) -> Self {
+        let isbns = standard_number::standard_numbers(record)
+            .into_iter()
+            .filter(|number| number.kind == standard_number::Kind::Isbn && number.is_usable())
+            .map(|number| number.number)
+            .collect();
+        let oclc_numbers = record
+            .data_fields()
+            .filter(|field| field.tag == "035")
+            .filter_map(|field| field.subfield("a"))
+            .filter_map(oclc_number)
+            .collect();
+        let title = record
+            .data_fields()
+            .find(|field| field.tag == "245")
+            .and_then(|field| {
+                let nonfiling = field
+                    .indicator
+                    .chars()
+                    .nth(1)
+                    .and_then(|c| c.to_digit(10))
+                    .unwrap_or(0) as usize;
+                let title = field
+                    .subfields
+                    .iter()
+                    .filter(|subfield| matches!(subfield.tag.as_str(), "a" | "n" | "p"))
+                    .map(|subfield| subfield.data.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                key(&title.chars().skip(nonfiling).collect::<String>())
+            });
+        let author = record
+            .data_fields()
+            .find(|field| matches!(field.tag.as_str(), "100" | "110" | "111"))
+            .and_then(|field| field.subfield("a"))
+            .and_then(|name| {
+                // The surname is enough, forenames are often abbreviated.
+                let surname = match name.split_once(',') {
+                    Some((surname, _)) => surname,
+                    None => name,
+                };
+                key(surname)
+            });
+
+        MatchKeys {
+            control_number: record
+                .control_field("001")
+                .map(|data| data.trim().to_string()),
+            isbns,
+            oclc_numbers,
+            title,
+            author,
+            year: crate::crosswalk::publication_year(record),
+        }
+    }
+
+    /// The fuzzy key, `title/author/year` with the title cut to 30 characters: records with the
+    /// same fuzzy key are likely duplicates.
+    pub fn fuzzy_key(&self) -> Option<String> {
+        let title = self.title.as_ref()?;
+        Some(format!(
+            "{}/{}/{}",
+            title.chars().take(30).collect::<String>().trim_end(),
+            self.author.as_deref().unwrap_or_default(),
+            self.year.as_deref().unwrap_or_default()
+        ))
+    }
+}
+
+/// `(OCoLC)ocm00012345` becomes `12345`; numbers of other agencies are ignored.
+fn oclc_number(data: &str) -> Option<String> {
+    let number = data.trim().strip_prefix("(OCoLC)")?.trim();
+    let number = OCLC_PREFIXES
+        .iter()
+        .find_map(|prefix| number.strip_prefix(prefix))
+        .unwrap_or(number)
+        .trim_start_matches('0');
+    Some(number.to_string())
+        .filter(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Lowercase letters and digits without diacritics, the words separated by one space.
+fn key(text: &str) -> Option<String> {
+    let text = normalize(text, Normalization::SearchKey).to_lowercase();
+    let key = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(key).filter(|key| !key.is_empty())
+}
+
+/// The similarity of two titles, the Dice coefficient of their character bigrams.
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |text: &str| {
+        let chars = text.chars().collect::<Vec<_>>();
+        chars
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>()
+    };
+    let a = bigrams(a);
+    let mut b = bigrams(b);
+    let total = a.len() + b.len();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut common = 0;
+    for bigram in &a {
+        if let Some(position) = b.iter().position(|other| other == bigram) {
+            b.swap_remove(position);
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / total as f64
+}
+
+/// A record of a batch: the position of the batch in the list and of the record in the batch.
+pub struct Member {
+    pub batch: usize,
+    pub position: usize,
+    pub control_number: Option<String>,
+}
+
+pub struct Cluster {
+    pub members: Vec<Member>,
+    pub confidence: f64,
+    pub matched_on: Vec<Strategy>,
+}
+
+/// Groups the records which match. Only the clusters of two records or more are returned, in
+/// the order of their first record.
+pub(crate) fn dedupe(batches: &[Vec<RecordWrapper>], options: &DedupeOptions) -> Vec<Cluster> {
+    let mut members = Vec::new();
+    let mut keys = Vec::new();
+    for (batch, records) in batches.iter().enumerate() {
+        for (position, record) in records.iter().enumerate() {
+            let record_keys = MatchKeys::new(record);
+            members.push(Member {
+                batch,
+                position,
+                control_number: record_keys.control_number.clone(),
+            });
+            keys.push(record_keys);
+        }
+    }
+
+    // The matching pairs of records, with the best confidence of the strategies which match.
+    // Only the records sharing a key of one of the strategies are compared.
+    let mut matches = Vec::new();
+    for (first, second) in candidate_pairs(&keys, options) {
+        let mut confidence: Option<f64> = None;
+        let mut strategies = Vec::new();
+        for strategy in &options.strategies {
+            if let Some(strategy_confidence) =
+                match_confidence(&keys[first], &keys[second], *strategy, options)
+            {
+                confidence = Some(confidence.unwrap_or(0.0).max(strategy_confidence));
+                strategies.push(*strategy);
+            }
+        }
+        if let Some(confidence) = confidence {
+            matches.push((first, second, confidence, strategies));
+        }
+    }
+
+    let mut parents = (0..keys.len()).collect::<Vec<_>>();
+    for (first, second, _, _) in &matches {
+        let (first, second) = (root(&mut parents, *first), root(&mut parents, *second));
+        if first != second {
+            parents[second.max(first)] = second.min(first);
+        }
+    }
+    // The position of the cluster of each root in `clusters`.
+    let mut positions: HashMap<usize, usize> = HashMap::new();
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (index, member) in members.into_iter().enumerate() {
+        let cluster_root = root(&mut parents, index);
+        match positions.get(&cluster_root) {
+            Some(&position) => clusters[position].members.push(member),
+            None => {
+                positions.insert(cluster_root, clusters.len());
+                clusters.push(Cluster {
+                    members: vec![member],
+                    confidence: 1.0,
+                    matched_on: Vec::new(),
+                });
+            }
+        }
+    }
+    for (first, _, confidence, strategies) in matches {
+        let cluster = &mut clusters[positions[&root(&mut parents, first)]];
+        cluster.confidence = cluster.confidence.min(confidence);
+        for strategy in strategies {
+            if !cluster.matched_on.contains(&strategy) {
+                cluster.matched_on.push(strategy);
+            }
+        }
+    }
+    clusters
+        .into_iter()
+        .filter(|cluster| cluster.members.len() > 1)
+        .collect()
+}
+
+/// The pairs of records sharing an OCLC number, an ISBN, or an author and a year, according to
+/// the strategies, in the order of the records. The other pairs cannot match, and the records
+/// without a title, an author or a year are not matched on the title.
+fn candidate_pairs(keys: &[MatchKeys], options: &DedupeOptions) -> BTreeSet<(usize, usize)> {
+    let mut groups: HashMap<(Strategy, Vec<&str>), Vec<usize>> = HashMap::new();
+    for (index, record_keys) in keys.iter().enumerate() {
+        for strategy in &options.strategies {
+            let group_keys = match strategy {
+                Strategy::Oclc => record_keys
+                    .oclc_numbers
+                    .iter()
+                    .map(|number| vec![number.as_str()])
+                    .collect(),
+                Strategy::Isbn => record_keys
+                    .isbns
+                    .iter()
+                    .map(|isbn| vec![isbn.as_str()])
+                    .collect(),
+                // The titles are compared within the records of the same author and year.
+                Strategy::TitleAuthor => match record_keys {
+                    MatchKeys {
+                        title: Some(_),
+                        author: Some(author),
+                        year: Some(year),
+                        ..
+                    } => vec![vec![author.as_str(), year.as_str()]],
+                    _ => Vec::new(),
+                },
+            };
+            for group_key in group_keys {
+                let group = groups.entry((*strategy, group_key)).or_default();
+                // A record with the same key twice is in the group once.
+                if group.last() != Some(&index) {
+                    group.push(index);
+                }
+            }
+        }
+    }
+    let mut pairs = BTreeSet::new();
+    for group in groups.values() {
+        for (position, &first) in group.iter().enumerate() {
+            for &second in &group[position + 1..] {
+                pairs.insert((first, second));
+            }
+        }
+    }
+    pairs
+}
+
+fn root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+fn match_confidence(
+    first: &MatchKeys,
+    second: &MatchKeys,
+    strategy: Strategy,
+    options: &DedupeOptions,
+) -> Option<f64> {
+    let shares = |a: &[String], b: &[String]| a.iter().any(|value| b.contains(value));
+    match strategy {
+        Strategy::Oclc => {
+            shares(&first.oclc_numbers, &second.oclc_numbers).then_some(OCLC_CONFIDENCE)
+        }
+        Strategy::Isbn => shares(&first.isbns, &second.isbns).then_some(ISBN_CONFIDENCE),
+        Strategy::TitleAuthor => {
+            // Without an author and a year, unrelated records with a common title would match.
+            if first.author.is_none() || first.year.is_none() {
+                return None;
+            }
+            if first.author != second.author || first.year != second.year {
+                return None;
+            }
+            let similarity = similarity(first.title.as_ref()?, second.title.as_ref()?);
+            (similarity >= options.threshold).then_some(TITLE_AUTHOR_CONFIDENCE * similarity)
+        }
+    }
+}
+
+impl Encoder for MatchKeys {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("control_number", self.control_number.encode(env)),
+                ("isbns", self.isbns.encode(env)),
+                ("oclc_numbers", self.oclc_numbers.encode(env)),
+                ("title", self.title.encode(env)),
+                ("author", self.author.encode(env)),
+                ("year", self.year.encode(env)),
+                ("fuzzy", self.fuzzy_key().encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+impl Encoder for Member {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("batch", self.batch.encode(env)),
+                ("position", self.position.encode(env)),
+                ("control_number", self.control_number.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+impl Encoder for Cluster {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let matched_on = self
+            .matched_on
+            .iter()
+            .map(|strategy| match strategy {
+                Strategy::Oclc => "oclc",
+                Strategy::Isbn => "isbn",
+                Strategy::TitleAuthor => "title_author",
+            })
+            .collect::<Vec<_>>();
+        Term::map_from_pairs(
+            env,
+            &[
+                ("records", self.members.encode(env)),
+                ("confidence", self.confidence.encode(env)),
+                ("matched_on", matched_on.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
@@ -0,0 +1,313 @@
+/// Comparison of two versions of a record, e.g. a record and the `corrected` version sent by a
+/// vendor. The leaders are compared position by position, the fields are aligned by tag and
+/// occurrence: the second 650 of one record is compared with the second 650 of the other one.
+/// The subfields of the changed data fields are aligned the same way, by code and occurrence.
+use rustler::{Encoder, Env, Term};
+
+use crate::raw::LEADER_LENGTH;
+use crate::{FieldWrapper, RecordWrapper, SubfieldWrapper};
+
+/// The record length (00-04) and the base address of data (12-16) are computed when the record
+/// is written, they are not compared.
+const COMPUTED_POSITIONS: [std::ops::Range<usize>; 2] = [0..5, 12..17];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Added,
+    Removed,
+    Changed,
+}
+
+pub struct LeaderChange {
+    pub position: usize,
+    pub from: char,
+    pub to: char,
+}
+
+pub struct SubfieldChange {
+    pub code: String,
+    /// The occurrence of the code in the field, starting at 1.
+    pub occurrence: usize,
+    pub kind: Kind,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+pub struct FieldChange {
+    pub tag: String,
+    /// The occurrence of the tag in the record, starting at 1.
+    pub occurrence: usize,
+    pub kind: Kind,
+    /// The field of each record in the mnemonic format.
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub indicators: Option<(String, String)>,
+    pub subfields: Vec<SubfieldChange>,
+}
+
+pub struct RecordDiff {
+    pub leader: Vec<LeaderChange>,
+    pub fields: Vec<FieldChange>,
+    /// The record with one line per field, prefixed by `-` for the lines of the first record
+    /// only, `+` for those of the second record only and a space for the unchanged ones.
+    pub unified: String,
+}
+
+impl RecordDiff {
+    pub fn is_empty(&self) -> bool {
+        self.leader.is_empty() && self.fields.is_empty()
+    }
+}
+
+pub(crate) fn diff(
+    a_leader: &[u8],
+    a: &RecordWrapper,
+    b_leader: &[u8],
+    b: &RecordWrapper,
+) -> RecordDiff {
+    let leader = (0..LEADER_LENGTH)
+        .filter(|position| {
+            !COMPUTED_POSITIONS
+                .iter()
+                .any(|range| range.contains(position))
+        })
+        .filter_map(|position| {
+            let from = *a_leader.get(position).unwrap_or(&b' ') as char;
+            let to = *b_leader.get(position).unwrap_or(&b' ') as char;
+            (from != to).then_some(LeaderChange { position, from, to })
+        })
+        .collect::<Vec<_>>();
+
+    let mut unified = String::new();
+    if leader.is_empty() {
+        unified.push_str(&format!(" {}\n", mnemonic_leader(a_leader)));
+    } else {
+        unified.push_str(&format!("-{}\n", mnemonic_leader(a_leader)));
+        unified.push_str(&format!("+{}\n", mnemonic_leader(b_leader)));
+    }
+
+    let mut tags = a
+        .fields
+        .iter()
+        .chain(&b.fields)
+        .map(|field| field.tag())
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+
+    let mut fields = Vec::new();
+    for tag in tags {
+        let a_fields = a.fields.iter().filter(|field| field.tag() == tag);
+        let b_fields = b.fields.iter().filter(|field| field.tag() == tag);
+        for (index, pair) in align(a_fields, b_fields).into_iter().enumerate() {
+            let occurrence = index + 1;
+            let change = match pair {
+                (Some(from), Some(to)) => changed_field(occurrence, from, to),
+                (Some(from), None) => Some(FieldChange {
+                    tag: tag.to_string(),
+                    occurrence,
+                    kind: Kind::Removed,
+                    from: Some(from.to_mnemonic()),
+                    to: None,
+                    indicators: None,
+                    subfields: Vec::new(),
+                }),
+                (None, Some(to)) => Some(FieldChange {
+                    tag: tag.to_string(),
+                    occurrence,
+                    kind: Kind::Added,
+                    from: None,
+                    to: Some(to.to_mnemonic()),
+                    indicators: None,
+                    subfields: Vec::new(),
+                }),
+                (None, None) => None,
+            };
+            match &change {
+                Some(change) => {
+                    if let Some(from) = &change.from {
+                        unified.push_str(&format!("-{}\n", from));
+                    }
+                    if let Some(to) = &change.to {
+                        unified.push_str(&format!("+{}\n", to));
+                    }
+                }
+                None => {
+                    if let (Some(field), _) = pair {
+                        unified.push_str(&format!(" {}\n", field.to_mnemonic()));
+                    }
+                }
+            }
+            fields.extend(change);
+        }
+    }
+
+    RecordDiff {
+        leader,
+        fields,
+        unified,
+    }
+}
+
+/// Pairs the items of both sides by position; the side with fewer items is padded with `None`.
+fn align<T>(a: impl Iterator<Item = T>, b: impl Iterator<Item = T>) -> Vec<(Option<T>, Option<T>)> {
+    let mut a = a.fuse();
+    let mut b = b.fuse();
+    let mut pairs = Vec::new();
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return pairs,
+            pair => pairs.push(pair),
+        }
+    }
+}
+
+/// The change between two fields with the same tag and occurrence, if they differ.
+fn changed_field(occurrence: usize, from: &FieldWrapper, to: &FieldWrapper) -> Option<FieldChange> {
+    let (indicators, subfields) = match (from, to) {
+        (FieldWrapper::Data(from), FieldWrapper::Data(to)) => {
+            let indicators = (from.indicator != to.indicator)
+                .then(|| (from.indicator.clone(), to.indicator.clone()));
+            let subfields = changed_subfields(&from.subfields, &to.subfields);
+            if indicators.is_none() && subfields.is_empty() {
+                return None;
+            }
+            (indicators, subfields)
+        }
+        (FieldWrapper::Control(from), FieldWrapper::Control(to)) if from.data == to.data => {
+            return None;
+        }
+        _ => (None, Vec::new()),
+    };
+    Some(FieldChange {
+        tag: from.tag().to_string(),
+        occurrence,
+        kind: Kind::Changed,
+        from: Some(from.to_mnemonic()),
+        to: Some(to.to_mnemonic()),
+        indicators,
+        subfields,
+    })
+}
+
+fn changed_subfields(from: &[SubfieldWrapper], to: &[SubfieldWrapper]) -> Vec<SubfieldChange> {
+    let mut codes = from
+        .iter()
+        .chain(to)
+        .map(|subfield| subfield.tag.as_str())
+        .collect::<Vec<_>>();
+    // The codes keep their order of appearance, which is meaningful in a field.
+    let mut seen = Vec::new();
+    codes.retain(|code| {
+        let is_new = !seen.contains(code);
+        seen.push(*code);
+        is_new
+    });
+
+    let mut changes = Vec::new();
+    for code in codes {
+        let from_values = from.iter().filter(|subfield| subfield.tag == code);
+        let to_values = to.iter().filter(|subfield| subfield.tag == code);
+        for (index, pair) in align(from_values, to_values).into_iter().enumerate() {
+            let kind = match pair {
+                (Some(from), Some(to)) if from.data == to.data => continue,
+                (Some(_), Some(_)) => Kind::Changed,
+                (Some(_), None) => Kind::Removed,
+                (None, Some(_)) => Kind::Added,
+                (None, None) => continue,
+            };
+            changes.push(SubfieldChange {
+                code: code.to_string(),
+                occurrence: index + 1,
+                kind,
+                from: pair.0.map(|subfield| subfield.data.clone()),
+                to: pair.1.map(|subfield| subfield.data.clone()),
+            });
+        }
+    }
+    changes
+}
+
+fn mnemonic_leader(leader: &[u8]) -> String {
+    format!(
+        "=LDR  {}",
+        String::from_utf8_lossy(leader).replace(' ', "\\")
+    )
+}
+
+impl Kind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Added => "added",
+            Kind::Removed => "removed",
+            Kind::Changed => "changed",
+        }
+    }
+}
+
+impl Encoder for LeaderChange {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("position", self.position.encode(env)),
+                ("from", self.from.to_string().encode(env)),
+                ("to", self.to.to_string().encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+impl Encoder for SubfieldChange {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("code", self.code.encode(env)),
+                ("occurrence", self.occurrence.encode(env)),
+                ("change", self.kind.as_str().encode(env)),
+                ("from", self.from.encode(env)),
+                ("to", self.to.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+impl Encoder for FieldChange {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let indicators = self.indicators.as_ref().map(|(from, to)| {
+            Term::map_from_pairs(env, &[("from", from.encode(env)), ("to", to.encode(env))])
+                .expect("Failed to create map: duplicate key")
+        });
+        Term::map_from_pairs(
+            env,
+            &[
+                ("tag", self.tag.encode(env)),
+                ("occurrence", self.occurrence.encode(env)),
+                ("change", self.kind.as_str().encode(env)),
+                ("from", self.from.encode(env)),
+                ("to", self.to.encode(env)),
+                ("indicators", indicators.encode(env)),
+                ("subfields", self.subfields.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+impl Encoder for RecordDiff {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("identical", self.is_empty().encode(env)),
+                ("leader", self.leader.encode(env)),
+                ("fields", self.fields.encode(env)),
+                ("unified", self.unified.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
@@ -0,0 +1,148 @@
+/// Conversion of MARC 21 bibliographic records to simple (unqualified) Dublin Core, following
+/// the crosswalk of the Library of Congress. The elements can be returned as a map of lists or
+/// as an `oai_dc` XML document, the format used by OAI-PMH.
+///
+/// Info about the crosswalk can be found here: https://www.loc.gov/marc/marc2dc.html
+use rustler::{Encoder, Env, Term};
+
+use crate::crosswalk::{self, escape_xml, subfields_text};
+use crate::leader::{BibliographicalLevelWrapper, RecordTypeWrapper};
+use crate::RecordWrapper;
+
+const CREATOR_TAGS: [&str; 7] = ["100", "110", "111", "700", "710", "711", "720"];
+const SUBJECT_TAGS: [&str; 6] = ["600", "610", "611", "630", "650", "653"];
+/// Notes which are mapped to other elements than description.
+const NOT_DESCRIPTION_TAGS: [&str; 4] = ["506", "530", "540", "546"];
+
+pub struct DublinCore {
+    elements: Vec<(&'static str, Vec<String>)>,
+}
+
+impl DublinCore {
+    pub(crate) fn new(record: &RecordWrapper) -> Self {
+        let values = |tags: &[&str], codes: &str| {
+            record
+                .data_fields()
+                .filter(|field| tags.contains(&field.tag.as_str()))
+                .filter_map(|field| subfields_text(field, codes))
+                .collect::<Vec<_>>()
+        };
+        let title = values(&["245"], "abfgknps");
+        let creator = values(&CREATOR_TAGS, "abcdgnq");
+        let subject = record
+            .data_fields()
+            .filter(|field| SUBJECT_TAGS.contains(&field.tag.as_str()))
+            .map(crosswalk::heading_text)
+            .filter(|subject| !subject.is_empty())
+            .collect();
+        let description = record
+            .data_fields()
+            .filter(|field| field.tag.starts_with('5'))
+            .filter(|field| !NOT_DESCRIPTION_TAGS.contains(&field.tag.as_str()))
+            .filter_map(|field| subfields_text(field, "a"))
+            .collect();
+        let publisher = crosswalk::publication_field(record)
+            .and_then(|field| subfields_text(field, "ab"))
+            .into_iter()
+            .collect();
+        let date = crosswalk::publication_date(record).into_iter().collect();
+        let mut identifier = values(&["020", "022", "024"], "a");
+        identifier.extend(values(&["856"], "u"));
+
+        let elements = vec![
+            ("title", title),
+            ("creator", creator),
+            ("subject", subject),
+            ("description", description),
+            ("publisher", publisher),
+            ("date", date),
+            ("type", resource_types(record)),
+            ("identifier", identifier),
+            ("language", crosswalk::languages(record)),
+        ];
+        // The same value often comes from several fields, e.g. a genre given in two
+        // thesauri.
+        let elements = elements
+            .into_iter()
+            .map(|(element, values)| {
+                let mut unique = Vec::with_capacity(values.len());
+                for value in values {
+                    if !unique.contains(&value) {
+                        unique.push(value);
+                    }
+                }
+                (element, unique)
+            })
+            .collect();
+        DublinCore { elements }
+    }
+
+    /// The elements as an `oai_dc:dc` XML document.
+    pub fn to_oai_dc(&self) -> String {
+        let mut xml = String::from(
+            "<oai_dc:dc xmlns:oai_dc=\"http://www.openarchives.org/OAI/2.0/oai_dc/\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+             xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+             xsi:schemaLocation=\"http://www.openarchives.org/OAI/2.0/oai_dc/ \
+             http://www.openarchives.org/OAI/2.0/oai_dc.xsd\">\n",
+        );
+        for (element, values) in &self.elements {
+            for value in values {
+                xml.push_str(&format!(
+                    "  <dc:{}>{}</dc:{}>\n",
+                    element,
+                    escape_xml(value),
+                    element
+                ));
+            }
+        }
+        xml.push_str("</oai_dc:dc>\n");
+        xml
+    }
+}
+
+impl Encoder for DublinCore {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let pairs = self
+            .elements
+            .iter()
+            .map(|(element, values)| (*element, values.encode(env)))
+            .collect::<Vec<_>>();
+        Term::map_from_pairs(env, &pairs).expect("Failed to create map: duplicate key")
+    }
+}
+
+/// The DCMI type of the record from leader/06 and /07, followed by the genres of 655.
+fn resource_types(record: &RecordWrapper) -> Vec<String> {
+    let mut types = Vec::new();
+    if *record.leader.bibliographic_level() == BibliographicalLevelWrapper::Collection {
+        types.push("Collection".to_string());
+    }
+    let dcmi_type = match record.leader.record_type() {
+        RecordTypeWrapper::LanguageMaterial
+        | RecordTypeWrapper::ManuscriptLanguageMaterial
+        | RecordTypeWrapper::NotatedMusic
+        | RecordTypeWrapper::ManuscriptNotatedMusic => Some("Text"),
+        RecordTypeWrapper::CartographicMaterial
+        | RecordTypeWrapper::ManuscriptCartographicMaterial
+        | RecordTypeWrapper::TwoDimensionalNonprojectableGraphic => Some("Image"),
+        RecordTypeWrapper::ProjectedMedium => Some("MovingImage"),
+        RecordTypeWrapper::NonmusicalSoundRecording | RecordTypeWrapper::MusicalSoundRecording => {
+            Some("Sound")
+        }
+        RecordTypeWrapper::ComputerFile => Some("Software"),
+        RecordTypeWrapper::Kit | RecordTypeWrapper::MixedMaterials => Some("Collection"),
+        RecordTypeWrapper::ThreeDimensionalArtifact => Some("PhysicalObject"),
+        RecordTypeWrapper::Multimedia => Some("InteractiveResource"),
+        _ => None,
+    };
+    types.extend(dcmi_type.map(str::to_string));
+    types.extend(
+        record
+            .data_fields()
+            .filter(|field| field.tag == "655")
+            .map(crosswalk::heading_text)
+            .filter(|genre| !genre.is_empty()),
+    );
+    types
+}
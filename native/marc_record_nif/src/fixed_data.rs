@@ -0,0 +1,51 @@
+/// Position by position decoding of fixed-length data elements (008), whose layout depends on
+/// the format of the record. Each coded value is turned into a label the same way the leader
+/// values are; unexpected codes are kept as is.
+use rustler::{Encoder, Env, Term};
+
+/// Code values of an element and their labels.
+pub type Codes = &'static [(char, &'static str)];
+
+/// Name, starting position, length and code values of each element of a field.
+pub type Layout = &'static [(&'static str, usize, usize, Codes)];
+
+pub struct FixedLengthData {
+    elements: Vec<(&'static str, String)>,
+}
+
+impl FixedLengthData {
+    pub fn new(data: &str, layout: Layout) -> Self {
+        let chars = data.chars().collect::<Vec<_>>();
+        let elements = layout
+            .iter()
+            .map(|(name, start, length, codes)| {
+                let value = chars.iter().skip(*start).take(*length).collect::<String>();
+                (*name, decode_code(&value, codes))
+            })
+            .collect();
+        FixedLengthData { elements }
+    }
+}
+
+impl Encoder for FixedLengthData {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let pairs = self
+            .elements
+            .iter()
+            .map(|(name, value)| (*name, value.encode(env)))
+            .collect::<Vec<_>>();
+        Term::map_from_pairs(env, &pairs).expect("Failed to create map: duplicate key")
+    }
+}
+
+fn decode_code(value: &str, codes: Codes) -> String {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some('|'), None) => "no_attempt_to_code".to_string(),
+        (Some(code), None) => codes
+            .iter()
+            .find(|(candidate, _)| *candidate == code)
+            .map_or_else(|| value.to_string(), |(_, label)| label.to_string()),
+        _ => value.to_string(),
+    }
+}
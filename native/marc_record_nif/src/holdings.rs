@@ -0,0 +1,338 @@
+/// Support for the MARC 21 Holdings format (leader/06 = `u`, `v`, `x` or `y`).
+///
+/// Besides the location of the items (852), serial holdings describe the issues owned with
+/// pairs of fields: a captions and pattern field (853, 854, 855) gives the caption of each
+/// level of enumeration and chronology, e.g. `v.` and `(year)`, and the enumeration and
+/// chronology fields (863, 864, 865) which share its link number in `$8` give the values. The
+/// pairs are expanded here into summary holdings statements such as `v.1 (1990)-v.5 (1994)`.
+///
+/// Info about the format can be found here: https://www.loc.gov/marc/holdings/
+use rustler::{Encoder, Env, Term};
+
+use crate::fixed_data::{FixedLengthData, Layout};
+use crate::{DataFieldWrapper, RecordWrapper};
+
+pub struct HoldingsRecord {
+    control_number: Option<String>,
+    linked_record_number: Option<String>,
+    fixed_length_data: Option<FixedLengthData>,
+    locations: Vec<Location>,
+    summary_holdings: Vec<String>,
+}
+
+impl HoldingsRecord {
+    pub(crate) fn new(record: &RecordWrapper) -> Self {
+        HoldingsRecord {
+            control_number: record.control_field("001").map(str::to_string),
+            linked_record_number: record.control_field("004").map(str::to_string),
+            fixed_length_data: record
+                .control_field("008")
+                .map(|data| FixedLengthData::new(data, HOLDINGS_008)),
+            locations: record
+                .data_fields()
+                .filter(|field| field.tag == "852")
+                .map(Location::new)
+                .collect(),
+            summary_holdings: summary_statements(record),
+        }
+    }
+}
+
+impl Encoder for HoldingsRecord {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("control_number", self.control_number.encode(env)),
+                (
+                    "linked_record_number",
+                    self.linked_record_number.encode(env),
+                ),
+                ("fixed_length_data", self.fixed_length_data.encode(env)),
+                ("locations", self.locations.encode(env)),
+                ("summary_holdings", self.summary_holdings.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+/// The 852 field, telling where the items are held and how they are shelved.
+pub struct Location {
+    institution: Option<String>,
+    sublocation: Option<String>,
+    shelving_location: Option<String>,
+    call_number: Option<String>,
+    copy_number: Option<String>,
+    public_note: Option<String>,
+    nonpublic_note: Option<String>,
+}
+
+impl Location {
+    fn new(field: &DataFieldWrapper) -> Self {
+        let subfield = |code| field.subfield(code).map(|data| data.trim().to_string());
+        // Call number prefix, classification part, item part and suffix.
+        let call_number = ["k", "h", "i", "m"]
+            .iter()
+            .filter_map(|code| field.subfield(code))
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Location {
+            institution: subfield("a"),
+            sublocation: subfield("b"),
+            shelving_location: subfield("c"),
+            call_number: Some(call_number).filter(|call_number| !call_number.is_empty()),
+            copy_number: subfield("t"),
+            public_note: subfield("z"),
+            nonpublic_note: subfield("x"),
+        }
+    }
+}
+
+impl Encoder for Location {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("institution", self.institution.encode(env)),
+                ("sublocation", self.sublocation.encode(env)),
+                ("shelving_location", self.shelving_location.encode(env)),
+                ("call_number", self.call_number.encode(env)),
+                ("copy_number", self.copy_number.encode(env)),
+                ("public_note", self.public_note.encode(env)),
+                ("nonpublic_note", self.nonpublic_note.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+const ENUMERATION_CODES: [&str; 6] = ["a", "b", "c", "d", "e", "f"];
+const CHRONOLOGY_CODES: [&str; 4] = ["i", "j", "k", "l"];
+
+/// Expands each enumeration and chronology field of the record with the captions of its
+/// captions and pattern field, in the order of the record.
+pub(crate) fn summary_statements(record: &RecordWrapper) -> Vec<String> {
+    let captions = record
+        .data_fields()
+        .filter(|field| ["853", "854", "855"].contains(&field.tag.as_str()))
+        .collect::<Vec<_>>();
+    record
+        .data_fields()
+        .filter_map(|values| {
+            let caption_tag = match values.tag.as_str() {
+                "863" => "853",
+                "864" => "854",
+                "865" => "855",
+                _ => return None,
+            };
+            let link = link_number(values)?;
+            let caption = captions
+                .iter()
+                .find(|caption| caption.tag == caption_tag && link_number(caption) == Some(link))?;
+            Some(expand(caption, values))
+        })
+        .collect()
+}
+
+/// The link number is the part of `$8` before the sequence number, e.g. `1` in `1.3`.
+fn link_number(field: &DataFieldWrapper) -> Option<&str> {
+    let link = field.subfield("8")?.trim();
+    Some(link.split('.').next().unwrap_or(link))
+}
+
+fn expand(caption: &DataFieldWrapper, values: &DataFieldWrapper) -> String {
+    let is_range = ENUMERATION_CODES
+        .iter()
+        .chain(CHRONOLOGY_CODES.iter())
+        .filter_map(|code| values.subfield(code))
+        .any(|value| value.contains('-'));
+    let start = statement(caption, values, |value| {
+        value.split_once('-').map_or(value, |(start, _)| start)
+    });
+    if !is_range {
+        return start;
+    }
+    let end = statement(caption, values, |value| {
+        value.split_once('-').map_or(value, |(_, end)| end)
+    });
+    format!("{}-{}", start, end)
+}
+
+/// Builds one end of a statement: the captioned enumeration levels separated by `:`, followed
+/// by the chronology in parentheses. `side` picks the start or the end of ranged values.
+fn statement(
+    caption: &DataFieldWrapper,
+    values: &DataFieldWrapper,
+    side: impl Fn(&str) -> &str,
+) -> String {
+    let value_of = |code: &str| {
+        values
+            .subfield(code)
+            .map(|value| side(value.trim()).trim())
+            .filter(|value| !value.is_empty())
+    };
+    let caption_of = |code: &str| caption.subfield(code).unwrap_or_default().trim();
+
+    let enumeration = ENUMERATION_CODES
+        .iter()
+        .filter_map(|code| {
+            let value = value_of(code)?;
+            let caption = caption_of(code);
+            if caption.starts_with('(') {
+                Some(value.to_string())
+            } else {
+                Some(format!("{}{}", caption, value))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(":");
+    let chronology = CHRONOLOGY_CODES
+        .iter()
+        .filter_map(|code| {
+            let value = value_of(code)?;
+            let caption = caption_of(code).to_lowercase();
+            if caption.contains("month") || caption.contains("season") {
+                Some(month_name(value).unwrap_or(value).to_string())
+            } else {
+                Some(value.to_string())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(":");
+
+    match (enumeration.is_empty(), chronology.is_empty()) {
+        (_, true) => enumeration,
+        (true, false) => chronology,
+        (false, false) => format!("{} ({})", enumeration, chronology),
+    }
+}
+
+/// Months and seasons are recorded as codes in the chronology values.
+fn month_name(code: &str) -> Option<&'static str> {
+    let name = match code {
+        "01" => "Jan.",
+        "02" => "Feb.",
+        "03" => "Mar.",
+        "04" => "Apr.",
+        "05" => "May",
+        "06" => "June",
+        "07" => "July",
+        "08" => "Aug.",
+        "09" => "Sept.",
+        "10" => "Oct.",
+        "11" => "Nov.",
+        "12" => "Dec.",
+        "21" => "Spring",
+        "22" => "Summer",
+        "23" => "Autumn",
+        "24" => "Winter",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Layout of the holdings 008 field.
+const HOLDINGS_008: Layout = &[
+    ("date_entered_on_file", 0, 6, &[]),
+    (
+        "receipt_or_acquisition_status",
+        6,
+        1,
+        &[
+            ('0', "unknown"),
+            ('1', "other_receipt_or_acquisition_status"),
+            ('2', "received_and_complete_or_ceased"),
+            ('3', "on_order"),
+            ('4', "currently_received"),
+            ('5', "not_currently_received"),
+        ],
+    ),
+    (
+        "method_of_acquisition",
+        7,
+        1,
+        &[
+            ('c', "cooperative_or_consortial_purchase"),
+            ('d', "deposit"),
+            ('e', "exchange"),
+            ('f', "free"),
+            ('g', "gift"),
+            ('l', "legal_deposit"),
+            ('m', "membership"),
+            ('n', "non_library_purchase"),
+            ('p', "purchase"),
+            ('q', "lease"),
+            ('u', "unknown"),
+            ('z', "other"),
+        ],
+    ),
+    ("expected_acquisition_end_date", 8, 4, &[]),
+    (
+        "general_retention_policy",
+        12,
+        1,
+        &[
+            ('0', "unknown"),
+            ('1', "other_general_retention_policy"),
+            ('2', "retained_except_as_replaced_by_updates"),
+            ('3', "sample_issue_retained"),
+            ('4', "retained_until_replaced_by_microform"),
+            (
+                '5',
+                "retained_until_replaced_by_cumulation_or_replacement_volume",
+            ),
+            ('6', "retained_for_a_limited_period"),
+            ('7', "not_retained"),
+            ('8', "permanently_retained"),
+        ],
+    ),
+    ("specific_retention_policy", 13, 3, &[]),
+    (
+        "completeness",
+        16,
+        1,
+        &[
+            ('0', "other"),
+            ('1', "complete"),
+            ('2', "incomplete"),
+            ('3', "very_incomplete"),
+            ('4', "not_applicable"),
+        ],
+    ),
+    ("number_of_copies_reported", 17, 3, &[]),
+    (
+        "lending_policy",
+        20,
+        1,
+        &[
+            ('a', "will_lend"),
+            ('b', "will_not_lend"),
+            ('c', "will_lend_hard_copy_only"),
+            ('l', "limited_lending_policy"),
+            ('u', "unknown"),
+        ],
+    ),
+    (
+        "reproduction_policy",
+        21,
+        1,
+        &[
+            ('a', "will_reproduce"),
+            ('b', "will_not_reproduce"),
+            ('u', "unknown"),
+        ],
+    ),
+    ("language", 22, 3, &[]),
+    (
+        "separate_or_composite_copy_report",
+        25,
+        1,
+        &[
+            ('0', "separate_copy_report"),
+            ('1', "composite_copy_report"),
+        ],
+    ),
+    ("date_of_report", 26, 6, &[]),
+];
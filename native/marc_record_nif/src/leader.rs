@@ -6,7 +6,10 @@ use marc_record::{
     MultipartResourceRecordLevel, RecordType, Status,
 };
 
+use crate::options::Flavor;
+
 pub struct LeaderWrapper {
+    flavor: Flavor,
     record_length: u16,
     status: StatusWrapper,
     record_type: RecordTypeWrapper,
@@ -17,11 +20,23 @@ pub struct LeaderWrapper {
     encoding_level: EncodingLevelWrapper,
     descriptive_cataloging_form: CatalogingFormWrapper,
     multipart_resource_record_level: MultipartResourceRecordLevelWrapper,
+    hierarchical_level: Option<HierarchicalLevelWrapper>,
 }
 
 impl LeaderWrapper {
-    pub fn new(leader: Leader) -> Self {
-        LeaderWrapper {
+    /// Builds the wrapper from the leader decoded by `marc-record`. The raw leader bytes are used
+    /// for the values `marc-record` does not know about, such as the ones of authority records,
+    /// or which have another meaning in UNIMARC.
+    pub fn new(leader: Leader, raw_leader: &[u8], flavor: Flavor) -> Self {
+        match flavor {
+            Flavor::Marc21 => Self::marc21(leader, raw_leader),
+            Flavor::Unimarc => Self::unimarc(leader, raw_leader),
+        }
+    }
+
+    fn marc21(leader: Leader, raw_leader: &[u8]) -> Self {
+        let wrapper = LeaderWrapper {
+            flavor: Flavor::Marc21,
             record_length: leader.record_length,
             status: StatusWrapper::new(leader.status),
             record_type: RecordTypeWrapper::new(leader.record_type),
@@ -36,47 +51,242 @@ impl LeaderWrapper {
             multipart_resource_record_level: MultipartResourceRecordLevelWrapper::new(
                 leader.multipart_resource_record_level,
             ),
+            hierarchical_level: None,
+        };
+        match raw_leader.get(6) {
+            Some(b'z') => wrapper.with_authority_values(raw_leader),
+            Some(b'u' | b'v' | b'x' | b'y') => wrapper.with_holdings_values(raw_leader),
+            _ => wrapper,
+        }
+    }
+
+    /// The UNIMARC leader shares the structure of the MARC 21 one but its coded values are
+    /// different. They are mapped to the closest MARC 21 values so that both flavors can be
+    /// handled the same way.
+    ///
+    /// Info about the format can be found here: https://www.ifla.org/g/unimarc-rg/unimarc-bibliographic/
+    fn unimarc(leader: Leader, raw_leader: &[u8]) -> Self {
+        let code = |position: usize| raw_leader.get(position).copied().unwrap_or(b' ');
+        let status = match code(5) {
+            b'c' => StatusWrapper::Corrected,
+            b'd' => StatusWrapper::Deleted,
+            b'o' => StatusWrapper::PreviouslyIssuedHigherLevel,
+            b'p' => StatusWrapper::IncreaseFromPrepublication,
+            _ => StatusWrapper::New,
+        };
+        let record_type = match code(6) {
+            b'b' => RecordTypeWrapper::ManuscriptLanguageMaterial,
+            b'c' => RecordTypeWrapper::NotatedMusic,
+            b'd' => RecordTypeWrapper::ManuscriptNotatedMusic,
+            b'e' => RecordTypeWrapper::CartographicMaterial,
+            b'f' => RecordTypeWrapper::ManuscriptCartographicMaterial,
+            b'g' => RecordTypeWrapper::ProjectedMedium,
+            b'i' => RecordTypeWrapper::NonmusicalSoundRecording,
+            b'j' => RecordTypeWrapper::MusicalSoundRecording,
+            b'k' => RecordTypeWrapper::TwoDimensionalNonprojectableGraphic,
+            b'l' => RecordTypeWrapper::ComputerFile,
+            b'm' => RecordTypeWrapper::Multimedia,
+            b'r' => RecordTypeWrapper::ThreeDimensionalArtifact,
+            _ => RecordTypeWrapper::LanguageMaterial,
+        };
+        let bibliographic_level = match code(7) {
+            b'a' => BibliographicalLevelWrapper::MonographicComponentPart,
+            b'c' => BibliographicalLevelWrapper::Collection,
+            b'i' => BibliographicalLevelWrapper::IntegratingResource,
+            b'm' => BibliographicalLevelWrapper::Monograph,
+            b's' => BibliographicalLevelWrapper::Serial,
+            _ => BibliographicalLevelWrapper::Unknown,
+        };
+        let hierarchical_level = match code(8) {
+            b'0' => HierarchicalLevelWrapper::NoHierarchicalRelationship,
+            b'1' => HierarchicalLevelWrapper::HighestLevel,
+            b'2' => HierarchicalLevelWrapper::BelowHighestLevel,
+            _ => HierarchicalLevelWrapper::Undefined,
+        };
+        let encoding_level = match code(17) {
+            b'1' => EncodingLevelWrapper::FullMaterialNotExamined,
+            b'2' => EncodingLevelWrapper::Prepublication,
+            b'3' => EncodingLevelWrapper::Partial,
+            _ => EncodingLevelWrapper::Full,
+        };
+        let descriptive_cataloging_form = match code(18) {
+            b'i' => CatalogingFormWrapper::PartialIsbd,
+            b'n' => CatalogingFormWrapper::NonIsbd,
+            _ => CatalogingFormWrapper::IsbdPunctuationIncluded,
+        };
+        LeaderWrapper {
+            flavor: Flavor::Unimarc,
+            record_length: leader.record_length,
+            status,
+            record_type,
+            bibliographic_level,
+            control_type: ControlTypeWrapper::Unspecified,
+            coding_scheme: CodingSchemeWrapper::new(leader.coding_scheme),
+            data_base_address: leader.data_base_address,
+            encoding_level,
+            descriptive_cataloging_form,
+            multipart_resource_record_level: MultipartResourceRecordLevelWrapper::NotApplicable,
+            hierarchical_level: Some(hierarchical_level),
+        }
+    }
+
+    pub fn flavor(&self) -> Flavor {
+        self.flavor
+    }
+
+    /// The same leader values for a record converted to the other flavor. The hierarchical level
+    /// only exists in UNIMARC.
+    pub fn with_flavor(self, flavor: Flavor) -> Self {
+        let hierarchical_level = match flavor {
+            Flavor::Marc21 => None,
+            Flavor::Unimarc => self
+                .hierarchical_level
+                .or(Some(HierarchicalLevelWrapper::Undefined)),
+        };
+        LeaderWrapper {
+            flavor,
+            hierarchical_level,
+            ..self
+        }
+    }
+
+    pub fn record_type(&self) -> &RecordTypeWrapper {
+        &self.record_type
+    }
+
+    pub fn bibliographic_level(&self) -> &BibliographicalLevelWrapper {
+        &self.bibliographic_level
+    }
+
+    pub fn is_archival(&self) -> bool {
+        self.control_type == ControlTypeWrapper::Archival
+    }
+
+    pub fn coding_scheme(&self) -> &CodingSchemeWrapper {
+        &self.coding_scheme
+    }
+
+    pub fn status(&self) -> &StatusWrapper {
+        &self.status
+    }
+
+    pub fn encoding_level(&self) -> &EncodingLevelWrapper {
+        &self.encoding_level
+    }
+
+    /// Whether the data may end with ISBD punctuation, according to the descriptive
+    /// cataloging form (leader/18).
+    pub fn has_punctuation(&self) -> bool {
+        !matches!(
+            self.descriptive_cataloging_form,
+            CatalogingFormWrapper::IsbdPunctuationOmitted
+                | CatalogingFormWrapper::NonIsbdPunctuationOmitted
+        )
+    }
+
+    pub fn is_authority(&self) -> bool {
+        self.record_type == RecordTypeWrapper::Authority
+    }
+
+    pub fn is_holdings(&self) -> bool {
+        matches!(
+            self.record_type,
+            RecordTypeWrapper::UnknownHoldings
+                | RecordTypeWrapper::MultipartItemHoldings
+                | RecordTypeWrapper::SinglePartItemHoldings
+                | RecordTypeWrapper::SerialItemHoldings
+        )
+    }
+
+    /// Authority records share the leader layout of bibliographic records but define their own
+    /// record type, additional status values and encoding levels.
+    fn with_authority_values(self, raw_leader: &[u8]) -> Self {
+        let status = match raw_leader[5] {
+            b's' => StatusWrapper::DeletedHeadingSplit,
+            b'x' => StatusWrapper::DeletedHeadingReplaced,
+            _ => self.status,
+        };
+        let encoding_level = match raw_leader.get(17) {
+            Some(b'n') => EncodingLevelWrapper::CompleteAuthority,
+            Some(b'o') => EncodingLevelWrapper::IncompleteAuthority,
+            _ => self.encoding_level,
+        };
+        LeaderWrapper {
+            status,
+            record_type: RecordTypeWrapper::Authority,
+            encoding_level,
+            ..self
+        }
+    }
+
+    /// Holdings records define their own record types and encoding levels. Position 18 tells
+    /// whether item information is present, which has nothing to do with a cataloging form.
+    fn with_holdings_values(self, raw_leader: &[u8]) -> Self {
+        let record_type = match raw_leader[6] {
+            b'v' => RecordTypeWrapper::MultipartItemHoldings,
+            b'x' => RecordTypeWrapper::SinglePartItemHoldings,
+            b'y' => RecordTypeWrapper::SerialItemHoldings,
+            _ => RecordTypeWrapper::UnknownHoldings,
+        };
+        let encoding_level = match raw_leader.get(17) {
+            Some(b'1') => EncodingLevelWrapper::HoldingsLevel1,
+            Some(b'2') => EncodingLevelWrapper::HoldingsLevel2,
+            Some(b'3') => EncodingLevelWrapper::HoldingsLevel3,
+            Some(b'4') => EncodingLevelWrapper::HoldingsLevel4,
+            Some(b'5') => EncodingLevelWrapper::HoldingsLevel4WithPieceDesignation,
+            Some(b'm') => EncodingLevelWrapper::MixedHoldingsLevel,
+            Some(b'z') => EncodingLevelWrapper::OtherHoldingsLevel,
+            _ => EncodingLevelWrapper::Unknown,
+        };
+        LeaderWrapper {
+            record_type,
+            encoding_level,
+            descriptive_cataloging_form: CatalogingFormWrapper::Unknown,
+            ..self
         }
     }
 }
 
 impl Encoder for LeaderWrapper {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        Term::map_from_pairs(
-            env,
-            &[
-                ("record_length", self.record_length.encode(env)),
-                ("status", self.status.encode(env)),
-                ("record_type", self.record_type.encode(env)),
-                (
-                    "bibliographical_level",
-                    self.bibliographic_level.encode(env),
-                ),
-                ("control_type", self.control_type.encode(env)),
-                ("coding_scheme", self.coding_scheme.encode(env)),
-                ("data_base_address", self.data_base_address.encode(env)),
-                ("encoding_level", self.encoding_level.encode(env)),
-                (
-                    "descriptive_cataloging_form",
-                    self.descriptive_cataloging_form.encode(env),
-                ),
-                (
-                    "multipart_resource_record_level",
-                    self.multipart_resource_record_level.encode(env),
-                ),
-            ],
-        )
-        .expect("Failed to create map: duplicate key")
+        let mut pairs = vec![
+            ("record_length", self.record_length.encode(env)),
+            ("status", self.status.encode(env)),
+            ("record_type", self.record_type.encode(env)),
+            (
+                "bibliographical_level",
+                self.bibliographic_level.encode(env),
+            ),
+            ("control_type", self.control_type.encode(env)),
+            ("coding_scheme", self.coding_scheme.encode(env)),
+            ("data_base_address", self.data_base_address.encode(env)),
+            ("encoding_level", self.encoding_level.encode(env)),
+            (
+                "descriptive_cataloging_form",
+                self.descriptive_cataloging_form.encode(env),
+            ),
+            (
+                "multipart_resource_record_level",
+                self.multipart_resource_record_level.encode(env),
+            ),
+        ];
+        if let Some(hierarchical_level) = &self.hierarchical_level {
+            pairs.push(("hierarchical_level", hierarchical_level.encode(env)));
+        }
+        Term::map_from_pairs(env, &pairs).expect("Failed to create map: duplicate key")
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum StatusWrapper {
+pub enum StatusWrapper {
     IncreaseInEncoding,
     Corrected,
     Deleted,
     New,
     IncreaseFromPrepublication,
+    DeletedHeadingSplit,
+    DeletedHeadingReplaced,
+    PreviouslyIssuedHigherLevel,
 }
 
 impl StatusWrapper {
@@ -89,6 +299,16 @@ impl StatusWrapper {
             Status::IncreaseFromPrepublication => StatusWrapper::IncreaseFromPrepublication,
         }
     }
+
+    /// Whether the record is to be removed, including the split and replaced authority headings.
+    pub fn is_deleted(&self) -> bool {
+        matches!(
+            self,
+            StatusWrapper::Deleted
+                | StatusWrapper::DeletedHeadingSplit
+                | StatusWrapper::DeletedHeadingReplaced
+        )
+    }
 }
 
 impl Encoder for StatusWrapper {
@@ -99,13 +319,16 @@ impl Encoder for StatusWrapper {
             StatusWrapper::Deleted => "deleted",
             StatusWrapper::New => "new",
             StatusWrapper::IncreaseFromPrepublication => "increase_from_prepublication",
+            StatusWrapper::DeletedHeadingSplit => "deleted_heading_split",
+            StatusWrapper::DeletedHeadingReplaced => "deleted_heading_replaced",
+            StatusWrapper::PreviouslyIssuedHigherLevel => "previously_issued_higher_level",
         };
         status.encode(env)
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum RecordTypeWrapper {
+pub enum RecordTypeWrapper {
     LanguageMaterial,
     NotatedMusic,
     ManuscriptNotatedMusic,
@@ -120,6 +343,12 @@ enum RecordTypeWrapper {
     MixedMaterials,
     ThreeDimensionalArtifact,
     ManuscriptLanguageMaterial,
+    Multimedia,
+    Authority,
+    UnknownHoldings,
+    MultipartItemHoldings,
+    SinglePartItemHoldings,
+    SerialItemHoldings,
 }
 
 impl RecordTypeWrapper {
@@ -147,9 +376,10 @@ impl RecordTypeWrapper {
     }
 }
 
-impl Encoder for RecordTypeWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        let record_type = match self {
+impl RecordTypeWrapper {
+    /// The name of the value, as returned to Elixir.
+    pub fn name(&self) -> &'static str {
+        match self {
             RecordTypeWrapper::LanguageMaterial => "language_material",
             RecordTypeWrapper::NotatedMusic => "notated_music",
             RecordTypeWrapper::ManuscriptNotatedMusic => "manuscript_notated_music",
@@ -166,13 +396,24 @@ impl Encoder for RecordTypeWrapper {
             RecordTypeWrapper::MixedMaterials => "mixed_materials",
             RecordTypeWrapper::ThreeDimensionalArtifact => "three_dimensional_artifact",
             RecordTypeWrapper::ManuscriptLanguageMaterial => "manuscript_language_material",
-        };
-        record_type.encode(env)
+            RecordTypeWrapper::Multimedia => "multimedia",
+            RecordTypeWrapper::Authority => "authority",
+            RecordTypeWrapper::UnknownHoldings => "unknown_holdings",
+            RecordTypeWrapper::MultipartItemHoldings => "multipart_item_holdings",
+            RecordTypeWrapper::SinglePartItemHoldings => "single_part_item_holdings",
+            RecordTypeWrapper::SerialItemHoldings => "serial_item_holdings",
+        }
+    }
+}
+
+impl Encoder for RecordTypeWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.name().encode(env)
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum BibliographicalLevelWrapper {
+pub enum BibliographicalLevelWrapper {
     MonographicComponentPart,
     SerialComponentPart,
     Collection,
@@ -204,9 +445,10 @@ impl BibliographicalLevelWrapper {
     }
 }
 
-impl Encoder for BibliographicalLevelWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        let bibliographic_level = match self {
+impl BibliographicalLevelWrapper {
+    /// The name of the value, as returned to Elixir.
+    pub fn name(&self) -> &'static str {
+        match self {
             BibliographicalLevelWrapper::MonographicComponentPart => "monographic_component_part",
             BibliographicalLevelWrapper::SerialComponentPart => "serial_component_part",
             BibliographicalLevelWrapper::Collection => "collection",
@@ -215,8 +457,13 @@ impl Encoder for BibliographicalLevelWrapper {
             BibliographicalLevelWrapper::Monograph => "monograph",
             BibliographicalLevelWrapper::Serial => "serial",
             BibliographicalLevelWrapper::Unknown => "unknown",
-        };
-        bibliographic_level.encode(env)
+        }
+    }
+}
+
+impl Encoder for BibliographicalLevelWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.name().encode(env)
     }
 }
 
@@ -260,13 +507,19 @@ impl CodingSchemeWrapper {
     }
 }
 
-impl Encoder for CodingSchemeWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        let coding_scheme = match self {
+impl CodingSchemeWrapper {
+    /// The name of the value, as returned to Elixir.
+    pub fn name(&self) -> &'static str {
+        match self {
             CodingSchemeWrapper::Marc8 => "marc8",
             CodingSchemeWrapper::Ucs => "ucs",
-        };
-        coding_scheme.encode(env)
+        }
+    }
+}
+
+impl Encoder for CodingSchemeWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.name().encode(env)
     }
 }
 
@@ -285,6 +538,15 @@ pub enum EncodingLevelWrapper {
     ObsoleteFull,
     ObsoleteMinimal,
     AddedFromBatch,
+    CompleteAuthority,
+    IncompleteAuthority,
+    HoldingsLevel1,
+    HoldingsLevel2,
+    HoldingsLevel3,
+    HoldingsLevel4,
+    HoldingsLevel4WithPieceDesignation,
+    MixedHoldingsLevel,
+    OtherHoldingsLevel,
 }
 
 impl EncodingLevelWrapper {
@@ -307,11 +569,28 @@ impl EncodingLevelWrapper {
             EncodingLevel::AddedFromBatch => EncodingLevelWrapper::AddedFromBatch,
         }
     }
+
+    /// How complete the description is, from 0 for unknown levels to 9 for full records.
+    pub fn completeness(&self) -> u8 {
+        match self {
+            EncodingLevelWrapper::Full | EncodingLevelWrapper::ObsoleteFull => 9,
+            EncodingLevelWrapper::FullMaterialNotExamined => 8,
+            EncodingLevelWrapper::Core => 7,
+            EncodingLevelWrapper::LessThanFullMaterialNotExamined => 6,
+            EncodingLevelWrapper::Abbreviated => 5,
+            EncodingLevelWrapper::Minimal | EncodingLevelWrapper::ObsoleteMinimal => 4,
+            EncodingLevelWrapper::Partial => 3,
+            EncodingLevelWrapper::AddedFromBatch => 2,
+            EncodingLevelWrapper::Prepublication => 1,
+            _ => 0,
+        }
+    }
 }
 
-impl Encoder for EncodingLevelWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        let encoding_level = match self {
+impl EncodingLevelWrapper {
+    /// The name of the value, as returned to Elixir.
+    pub fn name(&self) -> &'static str {
+        match self {
             EncodingLevelWrapper::Full => "full",
             EncodingLevelWrapper::FullMaterialNotExamined => "full_material_not_examined",
             EncodingLevelWrapper::LessThanFullMaterialNotExamined => {
@@ -327,8 +606,24 @@ impl Encoder for EncodingLevelWrapper {
             EncodingLevelWrapper::ObsoleteFull => "obsolete_full",
             EncodingLevelWrapper::ObsoleteMinimal => "obsolete_minimal",
             EncodingLevelWrapper::AddedFromBatch => "added_from_batch",
-        };
-        encoding_level.encode(env)
+            EncodingLevelWrapper::CompleteAuthority => "complete_authority",
+            EncodingLevelWrapper::IncompleteAuthority => "incomplete_authority",
+            EncodingLevelWrapper::HoldingsLevel1 => "holdings_level_1",
+            EncodingLevelWrapper::HoldingsLevel2 => "holdings_level_2",
+            EncodingLevelWrapper::HoldingsLevel3 => "holdings_level_3",
+            EncodingLevelWrapper::HoldingsLevel4 => "holdings_level_4",
+            EncodingLevelWrapper::HoldingsLevel4WithPieceDesignation => {
+                "holdings_level_4_with_piece_designation"
+            }
+            EncodingLevelWrapper::MixedHoldingsLevel => "mixed_holdings_level",
+            EncodingLevelWrapper::OtherHoldingsLevel => "other_holdings_level",
+        }
+    }
+}
+
+impl Encoder for EncodingLevelWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.name().encode(env)
     }
 }
 
@@ -339,6 +634,7 @@ pub enum CatalogingFormWrapper {
     IsbdPunctuationOmitted,
     IsbdPunctuationIncluded,
     NonIsbdPunctuationOmitted,
+    PartialIsbd,
     Unknown,
 }
 
@@ -367,6 +663,7 @@ impl Encoder for CatalogingFormWrapper {
             CatalogingFormWrapper::IsbdPunctuationOmitted => "isbd_punctuation_omitted",
             CatalogingFormWrapper::IsbdPunctuationIncluded => "isbd_punctuation_included",
             CatalogingFormWrapper::NonIsbdPunctuationOmitted => "non_isbd_punctuation_omitted",
+            CatalogingFormWrapper::PartialIsbd => "partial_isbd",
             CatalogingFormWrapper::Unknown => "unknown",
         };
         cataloging_form.encode(env)
@@ -413,3 +710,24 @@ impl Encoder for MultipartResourceRecordLevelWrapper {
         multipart_resource_record_level.encode(env)
     }
 }
+
+/// UNIMARC leader/08, the position of the record in a hierarchy of records.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HierarchicalLevelWrapper {
+    Undefined,
+    NoHierarchicalRelationship,
+    HighestLevel,
+    BelowHighestLevel,
+}
+
+impl Encoder for HierarchicalLevelWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let hierarchical_level = match self {
+            HierarchicalLevelWrapper::Undefined => "undefined",
+            HierarchicalLevelWrapper::NoHierarchicalRelationship => "no_hierarchical_relationship",
+            HierarchicalLevelWrapper::HighestLevel => "highest_level",
+            HierarchicalLevelWrapper::BelowHighestLevel => "below_highest_level",
+        };
+        hierarchical_level.encode(env)
+    }
+}
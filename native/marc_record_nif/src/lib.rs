@@ -9,62 +9,486 @@
 ///
 /// Each Wrapper implements the Encoder trait from the rustler crate to allow the data to be
 /// encoded in a Elixir term which is an Elixir data strcture.
-use marc_record::{parse_records, ControlField, DataField, Field, Record, Subfield};
+use marc_record::{ControlField, DataField, Field, Leader, Record, Subfield};
 
+use rayon::prelude::*;
 use rustler::{Binary, Encoder, Env, Error, NifResult, Term};
 
+use options::{DedupeOptions, Flavor, MergeOptions, ParseOptions, UpdateOptions};
+
+pub mod authority;
+pub mod bibframe;
+pub mod citation;
+pub mod crosswalk;
+pub mod dedupe;
+pub mod diff;
+pub mod dublin_core;
+pub mod fixed_data;
+pub mod holdings;
 pub mod leader;
+pub mod linkage;
+pub mod merge;
+pub mod mods;
+pub mod normalization;
+pub mod onix;
+pub mod options;
+pub mod punctuation;
+pub mod raw;
+pub mod schema_org;
+pub mod standard_number;
+pub mod stats;
+pub mod structure;
+pub mod summary;
+pub mod unimarc;
+pub mod update;
+pub mod validator;
+pub mod writer;
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn parse_records_wrapper<'a>(env: Env<'a>, data: Binary<'a>) -> NifResult<Vec<Term<'a>>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records
+        .iter()
+        .map(|record| record.encode_with(env, Some(data)))
+        .collect())
+}
+
+#[rustler::nif(name = "parse_records_wrapper", schedule = "DirtyCpu")]
+fn parse_records_with_options_wrapper<'a>(
+    env: Env<'a>,
+    data: Binary<'a>,
+    options: ParseOptions,
+) -> NifResult<Vec<Term<'a>>> {
+    let records = decode_records(data.as_slice(), &options)?;
+    Ok(records
+        .iter()
+        .map(|record| record.encode_with(env, Some(data)))
+        .collect())
+}
+
+/// Decodes the records like `parse_records_wrapper` without encoding them, and returns their
+/// number: `bench/encode.exs` compares both to measure the encoding into terms.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn decode_records_wrapper<'a>(data: Binary<'a>, options: ParseOptions) -> NifResult<usize> {
+    Ok(decode_records(data.as_slice(), &options)?.len())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn validate_records_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<Vec<validator::Issue>>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records.iter().map(validator::validate_record).collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn parse_records_with_warnings_wrapper<'a>(data: Binary<'a>) -> Vec<structure::CheckedRecord> {
+    raw::split_records(data.as_slice())
+        .iter()
+        .map(structure::CheckedRecord::new)
+        .collect()
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn authority_records_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<authority::AuthorityRecord>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records
+        .iter()
+        .filter(|record| record.leader.is_authority())
+        .map(authority::AuthorityRecord::new)
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn holdings_records_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<holdings::HoldingsRecord>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records
+        .iter()
+        .filter(|record| record.leader.is_holdings())
+        .map(holdings::HoldingsRecord::new)
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn unimarc_records_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<unimarc::UnimarcRecord>> {
+    let options = ParseOptions {
+        flavor: Flavor::Unimarc,
+        ..ParseOptions::default()
+    };
+    let records = decode_records(data.as_slice(), &options)?;
+    Ok(records.iter().map(unimarc::UnimarcRecord::new).collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn unimarc_to_marc21_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<RecordWrapper>> {
+    let options = ParseOptions {
+        flavor: Flavor::Unimarc,
+        ..ParseOptions::default()
+    };
+    let records = decode_records(data.as_slice(), &options)?;
+    Ok(records.into_iter().map(unimarc::to_marc21).collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn marc21_to_unimarc_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<RecordWrapper>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records.into_iter().map(unimarc::to_unimarc).collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_dublin_core_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<dublin_core::DublinCore>> {
+    convert_records(data.as_slice(), |record, _| {
+        dublin_core::DublinCore::new(record)
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_oai_dc_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    convert_records(data.as_slice(), |record, _| {
+        dublin_core::DublinCore::new(record).to_oai_dc()
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_mods_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    convert_records(data.as_slice(), |record, _| mods::to_mods(record))
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_bibframe_ntriples_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    convert_records(data.as_slice(), |record, position| {
+        bibframe::Graph::new(record, position).to_ntriples()
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_bibframe_turtle_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    convert_records(data.as_slice(), |record, position| {
+        bibframe::Graph::new(record, position).to_turtle()
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_schema_org_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    convert_records(data.as_slice(), |record, _| {
+        schema_org::to_schema_org(record)
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_ris_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    Ok(citations(data.as_slice())?
+        .iter()
+        .map(citation::Citation::to_ris)
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_bibtex_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    Ok(citations(data.as_slice())?
+        .iter()
+        .map(citation::Citation::to_bibtex)
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn to_csl_json_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<String>> {
+    Ok(citations(data.as_slice())?
+        .iter()
+        .map(citation::Citation::to_csl_json)
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn summary_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<summary::Summary>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records.iter().map(summary::Summary::new).collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn standard_numbers_wrapper<'a>(
+    data: Binary<'a>,
+) -> NifResult<Vec<Vec<standard_number::StandardNumber>>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records
+        .iter()
+        .map(standard_number::standard_numbers)
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn match_keys_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<dedupe::MatchKeys>> {
+    let records = decode_records(data.as_slice(), &ParseOptions::default())?;
+    Ok(records.iter().map(dedupe::MatchKeys::new).collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn dedupe_wrapper<'a>(
+    batches: Vec<Binary<'a>>,
+    options: DedupeOptions,
+) -> NifResult<Vec<dedupe::Cluster>> {
+    let batches = batches
+        .iter()
+        .map(|data| decode_records(data.as_slice(), &ParseOptions::default()))
+        .collect::<NifResult<Vec<_>>>()?;
+    Ok(dedupe::dedupe(&batches, &options))
+}
 
 #[rustler::nif]
-fn parse_records_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<RecordWrapper>> {
-    match parse_records(data.as_slice()) {
-        Ok(records) => {
-            let retval = records
-                .into_iter()
-                .map(RecordWrapper::new)
-                .collect::<Vec<_>>();
-            NifResult::Ok(retval)
-        }
-        Err(error) => {
-            let format_error = format!("Error in crate marc-record: {}", error);
-            NifResult::Err(Error::Term(Box::new(format_error)))
-        }
+fn diff_records_wrapper<'a>(a: Binary<'a>, b: Binary<'a>) -> NifResult<diff::RecordDiff> {
+    let (a_leader, b_leader) = (first_leader(a.as_slice()), first_leader(b.as_slice()));
+    let a_record = first_record(a.as_slice(), "first")?;
+    let b_record = first_record(b.as_slice(), "second")?;
+    Ok(diff::diff(a_leader, &a_record, b_leader, &b_record))
+}
+
+#[rustler::nif]
+fn merge_records_wrapper<'a>(
+    master: Binary<'a>,
+    incoming: Binary<'a>,
+    options: MergeOptions,
+) -> NifResult<merge::Merge> {
+    let master = first_record(master.as_slice(), "master")?;
+    let incoming = first_record(incoming.as_slice(), "incoming")?;
+    Ok(merge::merge(master, incoming, &options))
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn apply_updates_wrapper<'a>(
+    base: Binary<'a>,
+    updates: Binary<'a>,
+    options: UpdateOptions,
+) -> NifResult<update::Update> {
+    let base = decode_raw_records(base.as_slice(), &ParseOptions::default())?;
+    let updates = decode_raw_records(updates.as_slice(), &ParseOptions::default())?;
+    Ok(update::apply(&base, &updates, &options))
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn chunk_records_wrapper<'a>(data: Binary<'a>, chunk_size: usize) -> NifResult<Vec<Binary<'a>>> {
+    if chunk_size == 0 {
+        return Err(Error::BadArg);
     }
+    raw::chunk_ranges(data.as_slice(), chunk_size)
+        .into_iter()
+        .map(|range| data.make_subbinary(range.start, range.len()))
+        .collect()
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn record_ranges_wrapper<'a>(
+    data: Binary<'a>,
+    chunk_size: usize,
+) -> NifResult<Vec<(usize, usize)>> {
+    if chunk_size == 0 {
+        return Err(Error::BadArg);
+    }
+    Ok(raw::chunk_ranges(data.as_slice(), chunk_size)
+        .into_iter()
+        .map(|range| (range.start, range.len()))
+        .collect())
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn stats_wrapper<'a>(data: Binary<'a>, options: ParseOptions) -> stats::Stats {
+    stats::stats(data.as_slice(), &options)
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn onix_to_marc_wrapper<'a>(data: Binary<'a>) -> NifResult<Vec<RecordWrapper>> {
+    let records = onix::to_iso2709(data.as_slice()).map_err(|error| {
+        let format_error = format!("Error in ONIX message: {}", error);
+        Error::Term(Box::new(format_error))
+    })?;
+    decode_records(&records, &ParseOptions::default())
+}
+
+fn citations(data: &[u8]) -> NifResult<Vec<citation::Citation>> {
+    convert_records(data, citation::Citation::new)
 }
 
-struct RecordWrapper {
+/// The size from which the converters work on all the cores. Below it, spreading the records
+/// over the threads costs more than it saves.
+const PARALLEL_CONVERSION_BYTES: usize = 1 << 20;
+
+/// Decodes the records and converts each of them, in parallel for large binaries; the results
+/// keep the order of the records. The conversion is given the position of the record in the
+/// binary.
+fn convert_records<T: Send>(
+    data: &[u8],
+    convert: impl Fn(&RecordWrapper, usize) -> T + Sync,
+) -> NifResult<Vec<T>> {
+    let options = ParseOptions {
+        parallel: data.len() >= PARALLEL_CONVERSION_BYTES,
+        ..ParseOptions::default()
+    };
+    let records = decode_records(data, &options)?;
+    let convert = |(position, record)| convert(record, position);
+    Ok(if options.parallel {
+        records.par_iter().enumerate().map(convert).collect()
+    } else {
+        records.iter().enumerate().map(convert).collect()
+    })
+}
+
+/// The first record of the binary, for the NIFs working on a single record. The other records
+/// are not decoded.
+fn first_record(data: &[u8], name: &str) -> NifResult<RecordWrapper> {
+    let options = ParseOptions::default();
+    let raw = raw::first_record(data)
+        .ok_or_else(|| Error::Term(Box::new(format!("The {} binary has no record", name))))?;
+    let record = raw.parse(options.flavor).map_err(marc_record_error)?;
+    Ok(RecordWrapper::from_raw(record, &raw, &options))
+}
+
+/// The leader of the first record of the binary, as written.
+fn first_leader(data: &[u8]) -> &[u8] {
+    raw::first_record(data)
+        .and_then(|raw| raw.leader())
+        .unwrap_or_default()
+}
+
+/// Decodes every record of the binary and turns the `marc-record` error into a NIF error term.
+fn decode_records(data: &[u8], options: &ParseOptions) -> NifResult<Vec<RecordWrapper>> {
+    Ok(decode_raw_records(data, options)?
+        .into_iter()
+        .map(|(_, record)| record)
+        .collect())
+}
+
+/// Decodes every record of the binary, along with the bytes of the record as written. With the
+/// `parallel` option the records are decoded on all the cores; the first error in the order of
+/// the records is returned either way.
+///
+/// Public for the benchmarks only, like the wrappers: the crate is not meant to be used as a
+/// library.
+#[doc(hidden)]
+pub fn decode_raw_records<'a>(
+    data: &'a [u8],
+    options: &ParseOptions,
+) -> NifResult<Vec<(&'a [u8], RecordWrapper)>> {
+    let decode = |raw: &raw::RawRecord<'a>| {
+        let record = raw.parse(options.flavor)?;
+        Ok((raw.bytes, RecordWrapper::from_raw(record, raw, options)))
+    };
+    let raw_records = raw::split_records(data);
+    let records: Vec<Result<_, marc_record::Error>> = if options.parallel {
+        raw_records.par_iter().map(decode).collect()
+    } else {
+        raw_records.iter().map(decode).collect()
+    };
+    records
+        .into_iter()
+        .map(|record| record.map_err(marc_record_error))
+        .collect()
+}
+
+/// Turns the `marc-record` error into a NIF error term.
+fn marc_record_error(error: marc_record::Error) -> Error {
+    let format_error = format!("Error in crate marc-record: {}", error);
+    Error::Term(Box::new(format_error))
+}
+
+#[doc(hidden)]
+pub struct RecordWrapper {
     pub leader: leader::LeaderWrapper,
     pub fields: Vec<FieldWrapper>,
 }
 
 impl RecordWrapper {
-    pub fn new(record: Record) -> Self {
+    pub fn new(record: Record, raw_leader: &[u8], options: &ParseOptions) -> Self {
         let fields = Self::get_record_fields(record.fields);
-        let leader = leader::LeaderWrapper::new(record.leader);
+        Self::with_fields(record.leader, fields, raw_leader, options)
+    }
+
+    /// Like `new`, but the subfields of UTF-8 records also keep the position of their data in
+    /// the input, so that it can be returned without being copied.
+    pub(crate) fn from_raw(record: Record, raw: &raw::RawRecord, options: &ParseOptions) -> Self {
+        let raw_leader = raw.leader().unwrap_or_default();
+        let mut fields = Self::get_record_fields(record.fields);
+        if raw_leader.get(9) == Some(&b'a') {
+            Self::set_sources(&mut fields, raw);
+        }
+        Self::with_fields(record.leader, fields, raw_leader, options)
+    }
+
+    fn with_fields(
+        leader: Leader,
+        mut fields: Vec<FieldWrapper>,
+        raw_leader: &[u8],
+        options: &ParseOptions,
+    ) -> Self {
+        let leader = leader::LeaderWrapper::new(leader, raw_leader, options.flavor);
+        if options.strip_punctuation && leader.has_punctuation() {
+            punctuation::strip_fields(&mut fields);
+        }
+        if let Some(form) = options.normalization {
+            normalization::normalize_fields(&mut fields, form);
+        }
+        if options.link_fields {
+            fields = linkage::link_fields(fields);
+        }
         RecordWrapper { leader, fields }
     }
 
+    /// The data of the first control field with the given tag.
+    pub fn control_field(&self, tag: &str) -> Option<&str> {
+        self.fields.iter().find_map(|field| match field {
+            FieldWrapper::Control(control) if control.tag == tag => Some(control.data.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn data_fields(&self) -> impl Iterator<Item = &DataFieldWrapper> {
+        self.fields.iter().filter_map(|field| match field {
+            FieldWrapper::Data(data) => Some(data),
+            FieldWrapper::Control(_) => None,
+        })
+    }
+
     fn get_record_fields(fields: Vec<Field>) -> Vec<FieldWrapper> {
-        fields
-            .into_iter()
-            .map(|field| FieldWrapper::new(field))
-            .collect()
+        fields.into_iter().map(FieldWrapper::new).collect()
     }
-}
 
-impl Encoder for RecordWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+    /// The fields are in the order of the directory; the positions are only kept when the
+    /// fields and subfields match the directory one to one.
+    fn set_sources(fields: &mut [FieldWrapper], raw: &raw::RawRecord) {
+        let Some(ranges) = raw.subfield_ranges() else {
+            return;
+        };
+        if ranges.len() != fields.len() {
+            return;
+        }
+        for (field, ranges) in fields.iter_mut().zip(ranges) {
+            if let FieldWrapper::Data(data) = field {
+                if data.subfields.len() == ranges.len() {
+                    for (subfield, range) in data.subfields.iter_mut().zip(ranges) {
+                        subfield.source = Some(range);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Encodes the record. Given the binary the record was decoded from, the subfield data
+    /// unchanged since decoding is returned as sub-binaries of it rather than as new binaries.
+    pub fn encode_with<'a>(&self, env: Env<'a>, source: Option<Binary<'a>>) -> Term<'a> {
         let leader = self.leader.encode(env);
         let mut record_fields = Term::list_new_empty(env);
         for field in &self.fields {
-            record_fields = record_fields.list_prepend(field.encode(env));
+            record_fields = record_fields.list_prepend(field.encode_with(env, source));
         }
         Term::map_from_pairs(env, &[("fields", record_fields), ("leader", leader)])
             .expect("Failed to create map: duplicate key")
     }
 }
 
-enum FieldWrapper {
+impl Encoder for RecordWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.encode_with(env, None)
+    }
+}
+
+#[doc(hidden)]
+pub enum FieldWrapper {
     Data(DataFieldWrapper),
     Control(ControlFieldWrapper),
 }
@@ -76,18 +500,51 @@ impl FieldWrapper {
             Field::Data(data) => FieldWrapper::Data(DataFieldWrapper::new(data)),
         }
     }
+
+    pub fn tag(&self) -> &str {
+        match self {
+            FieldWrapper::Data(data) => &data.tag,
+            FieldWrapper::Control(control) => &control.tag,
+        }
+    }
+
+    /// The field as a line of the mnemonic format of MarcEdit, e.g. `=245  10$aTitle`. The
+    /// blanks of the indicators and of the control fields are written as `\`.
+    pub fn to_mnemonic(&self) -> String {
+        match self {
+            FieldWrapper::Control(control) => {
+                format!("={}  {}", control.tag, control.data.replace(' ', "\\"))
+            }
+            FieldWrapper::Data(data) => {
+                let mut line = format!("={}  {}", data.tag, data.indicator.replace(' ', "\\"));
+                for subfield in &data.subfields {
+                    line.push('$');
+                    line.push_str(&subfield.tag);
+                    line.push_str(&subfield.data);
+                }
+                line
+            }
+        }
+    }
 }
 
-impl Encoder for FieldWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+impl FieldWrapper {
+    fn encode_with<'a>(&self, env: Env<'a>, source: Option<Binary<'a>>) -> Term<'a> {
         match self {
-            FieldWrapper::Data(data) => data.encode(env),
+            FieldWrapper::Data(data) => data.encode_with(env, source),
             FieldWrapper::Control(control) => control.encode(env),
         }
     }
 }
 
-struct ControlFieldWrapper {
+impl Encoder for FieldWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.encode_with(env, None)
+    }
+}
+
+#[doc(hidden)]
+pub struct ControlFieldWrapper {
     pub tag: String,
     pub data: String,
 }
@@ -111,10 +568,13 @@ impl Encoder for ControlFieldWrapper {
     }
 }
 
-struct DataFieldWrapper {
+#[doc(hidden)]
+pub struct DataFieldWrapper {
     pub tag: String,
     pub indicator: String,
     pub subfields: Vec<SubfieldWrapper>,
+    /// The alternate graphic representation (880) of the field, when linked.
+    pub linked: Option<Box<DataFieldWrapper>>,
 }
 
 impl DataFieldWrapper {
@@ -123,9 +583,18 @@ impl DataFieldWrapper {
             tag: data.tag.to_string(),
             indicator: Self::build_indicator(&data.indicator),
             subfields: Self::build_subfields(&data.subfields),
+            linked: None,
         }
     }
 
+    /// The data of the first subfield with the given code.
+    pub fn subfield(&self, code: &str) -> Option<&str> {
+        self.subfields
+            .iter()
+            .find(|subfield| subfield.tag == code)
+            .map(|subfield| subfield.data.as_str())
+    }
+
     fn build_indicator(indicator: &[char]) -> String {
         indicator.iter().fold(String::new(), |mut acc, &c| {
             acc.push(c);
@@ -139,38 +608,79 @@ impl DataFieldWrapper {
             .map(|subfield| SubfieldWrapper {
                 tag: subfield.tag.to_string(),
                 data: subfield.data.clone(),
+                source: None,
             })
             .collect()
     }
 }
 
-impl Encoder for DataFieldWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+impl DataFieldWrapper {
+    fn encode_with<'a>(&self, env: Env<'a>, source: Option<Binary<'a>>) -> Term<'a> {
         let field_type = ("type", "data".encode(env));
         let tag = ("tag", self.tag.trim().encode(env));
         let indicator = ("indicator", self.indicator.trim().encode(env));
         let mut subfields_list = Term::list_new_empty(env);
         for subfield in &self.subfields {
-            subfields_list = subfields_list.list_prepend(subfield.encode(env));
+            subfields_list = subfields_list.list_prepend(subfield.encode_with(env, source));
         }
         let subfields = ("subfields", subfields_list);
-        Term::map_from_pairs(env, &[field_type, tag, indicator, subfields])
-            .expect("Failed to create map: duplicate key")
+        let mut pairs = vec![field_type, tag, indicator, subfields];
+        if let Some(linked) = &self.linked {
+            let linkage = linked.linkage();
+            let script = linkage.as_ref().and_then(|linkage| linkage.script_name());
+            let right_to_left = linkage.is_some_and(|linkage| linkage.right_to_left);
+            pairs.push(("linked", linked.encode_with(env, source)));
+            pairs.push(("script", script.encode(env)));
+            pairs.push(("right_to_left", right_to_left.encode(env)));
+        }
+        Term::map_from_pairs(env, &pairs).expect("Failed to create map: duplicate key")
     }
 }
 
-struct SubfieldWrapper {
+impl Encoder for DataFieldWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.encode_with(env, None)
+    }
+}
+
+#[doc(hidden)]
+pub struct SubfieldWrapper {
     pub tag: String,
     pub data: String,
+    /// The position of the data in the binary the record was decoded from, when the data is
+    /// made of those bytes as is (UTF-8 records).
+    pub source: Option<std::ops::Range<usize>>,
 }
 
-impl Encoder for SubfieldWrapper {
-    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+impl SubfieldWrapper {
+    fn encode_with<'a>(&self, env: Env<'a>, source: Option<Binary<'a>>) -> Term<'a> {
         let tag = ("tag", self.tag.trim().encode(env));
-        let data = ("data", self.data.trim().encode(env));
+        let data = match self.sub_binary(source) {
+            Some(binary) => ("data", binary.encode(env)),
+            None => ("data", self.data.trim().encode(env)),
+        };
 
         Term::map_from_pairs(env, &[tag, data]).expect("Failed to create map: duplicate key")
     }
+
+    /// The trimmed data as a sub-binary of the source, unless the data was changed after
+    /// decoding, e.g. normalized.
+    fn sub_binary<'a>(&self, source: Option<Binary<'a>>) -> Option<Binary<'a>> {
+        let source = source?;
+        let range = self.source.clone()?;
+        if source.as_slice().get(range.clone())? != self.data.as_bytes() {
+            return None;
+        }
+        let trimmed = self.data.trim_start();
+        let start = range.start + self.data.len() - trimmed.len();
+        source.make_subbinary(start, trimmed.trim_end().len()).ok()
+    }
+}
+
+impl Encoder for SubfieldWrapper {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        self.encode_with(env, None)
+    }
 }
 
 rustler::init!("Elixir.MarcRecord");
@@ -0,0 +1,112 @@
+/// Pairing of the alternate graphic representation fields (880) with the regular fields they
+/// transcribe. A title in Japanese is recorded romanized in 245 and in Japanese script in an
+/// 880; both carry a linkage subfield (`$6`) with the same occurrence number: `880-02` in the
+/// 245 and `245-02/$1` in the 880, where `$1` identifies the script.
+///
+/// Info about the linkage can be found here: https://www.loc.gov/marc/bibliographic/ecbdcntf.html
+use crate::{DataFieldWrapper, FieldWrapper};
+
+/// The script identification codes of `$6` and their names.
+const SCRIPTS: [(&str, &str); 6] = [
+    ("(3", "arabic"),
+    ("(B", "latin"),
+    ("$1", "cjk"),
+    ("(N", "cyrillic"),
+    ("(S", "greek"),
+    ("(2", "hebrew"),
+];
+
+/// The content of a linkage subfield: `245-02/$1/r` is the tag and occurrence of the linked
+/// field, the script and the orientation of the text.
+pub struct Linkage<'a> {
+    pub tag: &'a str,
+    pub occurrence: &'a str,
+    pub script: Option<&'a str>,
+    pub right_to_left: bool,
+}
+
+impl<'a> Linkage<'a> {
+    pub fn parse(data: &'a str) -> Option<Self> {
+        let mut parts = data.trim().split('/');
+        let (tag, occurrence) = parts.next()?.split_once('-')?;
+        if tag.len() != 3 || occurrence.len() < 2 {
+            return None;
+        }
+        let script = parts.next().filter(|script| !script.is_empty());
+        let right_to_left = parts.next() == Some("r");
+        Some(Linkage {
+            tag,
+            occurrence,
+            script,
+            right_to_left,
+        })
+    }
+
+    /// The name of the script, or the code itself when it is not a MARC-8 one (ISO 15924
+    /// codes such as `Hang` are used too).
+    pub fn script_name(&self) -> Option<&'a str> {
+        let script = self.script?;
+        Some(
+            SCRIPTS
+                .iter()
+                .find(|(code, _)| *code == script)
+                .map_or(script, |(_, name)| name),
+        )
+    }
+
+    /// Fields with the occurrence `00` have no counterpart, e.g. a note only recorded in the
+    /// original script.
+    pub fn is_paired(&self) -> bool {
+        self.occurrence != "00"
+    }
+}
+
+impl DataFieldWrapper {
+    pub fn linkage(&self) -> Option<Linkage<'_>> {
+        Linkage::parse(self.subfield("6")?)
+    }
+}
+
+/// Moves each 880 into the regular field it is linked to. The 880 fields without a counterpart
+/// stay where they are.
+pub(crate) fn link_fields(fields: Vec<FieldWrapper>) -> Vec<FieldWrapper> {
+    // The positions of the regular field and of the 880 of each pair.
+    let mut pairs = Vec::new();
+    for (position, field) in fields.iter().enumerate() {
+        let FieldWrapper::Data(alternate) = field else {
+            continue;
+        };
+        if alternate.tag != "880" {
+            continue;
+        }
+        let Some(linkage) = alternate.linkage().filter(|linkage| linkage.is_paired()) else {
+            continue;
+        };
+        let regular = fields.iter().position(|field| match field {
+            FieldWrapper::Data(regular) => {
+                regular.tag == linkage.tag
+                    && regular.linkage().is_some_and(|regular_linkage| {
+                        regular_linkage.tag == "880"
+                            && regular_linkage.occurrence == linkage.occurrence
+                    })
+            }
+            FieldWrapper::Control(_) => false,
+        });
+        if let Some(regular) = regular {
+            if pairs.iter().all(|(paired, _)| *paired != regular) {
+                pairs.push((regular, position));
+            }
+        }
+    }
+
+    let mut slots = fields.into_iter().map(Some).collect::<Vec<_>>();
+    for (regular, alternate) in pairs {
+        let Some(FieldWrapper::Data(alternate)) = slots[alternate].take() else {
+            continue;
+        };
+        if let Some(FieldWrapper::Data(regular)) = &mut slots[regular] {
+            regular.linked = Some(Box::new(alternate));
+        }
+    }
+    slots.into_iter().flatten().collect()
+}
@@ -0,0 +1,207 @@
+/// Merging of an incoming record, e.g. from a vendor, into a master record. The fields of each
+/// tag follow a policy: keep the master fields, replace them by the incoming ones, append the
+/// incoming ones or add only the incoming fields the master record does not have.
+///
+/// The tags without policy follow the most complete record according to the encoding level
+/// (leader/17): the incoming fields replace the master ones only when the incoming record is
+/// more complete. Its leader is taken too. Every field added or removed is recorded in the
+/// change log.
+use rustler::{Encoder, Env, Term};
+
+use crate::normalization::normalize;
+use crate::options::{MergeOptions, Normalization, Policy};
+use crate::punctuation;
+use crate::{FieldWrapper, RecordWrapper};
+
+pub struct Merge {
+    pub(crate) record: RecordWrapper,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Added,
+    Removed,
+}
+
+pub struct Change {
+    pub tag: String,
+    pub policy: Policy,
+    pub action: Action,
+    /// The field in the mnemonic format, e.g. `=856  40$uhttps://example.com`.
+    pub field: String,
+}
+
+/// The policy of the most specific pattern matching the tag: `856` before `85X` before `8XX`.
+fn policy_of(tag: &str, options: &MergeOptions) -> Option<Policy> {
+    options
+        .policies
+        .iter()
+        .filter(|(pattern, _)| {
+            pattern.len() == tag.len()
+                && pattern
+                    .chars()
+                    .zip(tag.chars())
+                    .all(|(p, t)| p == 'X' || p == t)
+        })
+        .max_by_key(|(pattern, _)| pattern.chars().filter(|c| *c != 'X').count())
+        .map(|(_, policy)| *policy)
+}
+
+/// Two fields are the same when they differ only by case, diacritics and ISBD punctuation.
+fn same_field(a: &FieldWrapper, b: &FieldWrapper) -> bool {
+    comparison_key(a) == comparison_key(b)
+}
+
+fn comparison_key(field: &FieldWrapper) -> String {
+    match field {
+        FieldWrapper::Control(control) => control.data.clone(),
+        FieldWrapper::Data(data) => {
+            let mut key = data.indicator.clone();
+            for subfield in &data.subfields {
+                let value = punctuation::strip(subfield.data.trim(), false);
+                key.push('\u{1f}');
+                key.push_str(&subfield.tag);
+                key.push_str(&normalize(value, Normalization::SearchKey).to_lowercase());
+            }
+            key
+        }
+    }
+}
+
+pub(crate) fn merge(
+    master: RecordWrapper,
+    incoming: RecordWrapper,
+    options: &MergeOptions,
+) -> Merge {
+    let incoming_is_better = incoming.leader.encoding_level().completeness()
+        > master.leader.encoding_level().completeness();
+    let default_policy = if incoming_is_better {
+        Policy::Replace
+    } else {
+        Policy::Keep
+    };
+    let leader = if incoming_is_better {
+        incoming.leader
+    } else {
+        master.leader
+    };
+
+    let mut tags = master
+        .fields
+        .iter()
+        .chain(&incoming.fields)
+        .map(|field| field.tag().to_string())
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+
+    let mut master_fields = master.fields.into_iter().map(Some).collect::<Vec<_>>();
+    let mut incoming_fields = incoming.fields.into_iter().map(Some).collect::<Vec<_>>();
+    let take = |fields: &mut Vec<Option<FieldWrapper>>, tag: &str| {
+        fields
+            .iter_mut()
+            .filter(|field| field.as_ref().is_some_and(|field| field.tag() == tag))
+            .filter_map(Option::take)
+            .collect::<Vec<_>>()
+    };
+
+    let mut fields = Vec::new();
+    let mut changes = Vec::new();
+    for tag in tags {
+        let policy = policy_of(&tag, options).unwrap_or(default_policy);
+        let ours = take(&mut master_fields, &tag);
+        let theirs = take(&mut incoming_fields, &tag);
+        let mut log = |action: Action, field: &FieldWrapper| {
+            changes.push(Change {
+                tag: tag.clone(),
+                policy,
+                action,
+                field: field.to_mnemonic(),
+            })
+        };
+        match policy {
+            Policy::Keep => fields.extend(ours),
+            Policy::Replace if theirs.is_empty() => fields.extend(ours),
+            Policy::Replace => {
+                // Unchanged fields are not logged as removed and added again.
+                for field in &ours {
+                    if !theirs.iter().any(|other| same_field(field, other)) {
+                        log(Action::Removed, field);
+                    }
+                }
+                for field in &theirs {
+                    if !ours.iter().any(|other| same_field(field, other)) {
+                        log(Action::Added, field);
+                    }
+                }
+                fields.extend(theirs);
+            }
+            Policy::Append => {
+                for field in &theirs {
+                    log(Action::Added, field);
+                }
+                fields.extend(ours);
+                fields.extend(theirs);
+            }
+            Policy::Union => {
+                let mut added = Vec::new();
+                for field in theirs {
+                    let is_new = !ours
+                        .iter()
+                        .chain(&added)
+                        .any(|other| same_field(&field, other));
+                    if is_new {
+                        log(Action::Added, &field);
+                        added.push(field);
+                    }
+                }
+                fields.extend(ours);
+                fields.extend(added);
+            }
+        }
+    }
+
+    Merge {
+        record: RecordWrapper { leader, fields },
+        changes,
+    }
+}
+
+impl Encoder for Merge {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("record", self.record.encode(env)),
+                ("changes", self.changes.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+impl Encoder for Change {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let policy = match self.policy {
+            Policy::Keep => "keep",
+            Policy::Replace => "replace",
+            Policy::Append => "append",
+            Policy::Union => "union",
+        };
+        let action = match self.action {
+            Action::Added => "added",
+            Action::Removed => "removed",
+        };
+        Term::map_from_pairs(
+            env,
+            &[
+                ("tag", self.tag.encode(env)),
+                ("policy", policy.encode(env)),
+                ("action", action.encode(env)),
+                ("field", self.field.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
@@ -0,0 +1,475 @@
+/// Conversion of MARC 21 bibliographic records to MODS 3.8 XML, covering the common cases of
+/// the MARC21slim2MODS stylesheet of the Library of Congress.
+///
+/// Info about the mapping can be found here: https://www.loc.gov/standards/mods/mods-mapping.html
+use crate::crosswalk::{self, subfields_text, XmlWriter};
+use crate::leader::{BibliographicalLevelWrapper, RecordTypeWrapper};
+use crate::punctuation;
+use crate::{DataFieldWrapper, RecordWrapper};
+
+const MODS_NAMESPACE: &str = "http://www.loc.gov/mods/v3";
+const MODS_SCHEMA: &str =
+    "http://www.loc.gov/mods/v3 http://www.loc.gov/standards/mods/v3/mods-3-8.xsd";
+
+/// Converts a record to a `mods` document.
+pub(crate) fn to_mods(record: &RecordWrapper) -> String {
+    let mut xml = XmlWriter::new();
+    xml.start(
+        "mods",
+        &[
+            ("xmlns", MODS_NAMESPACE),
+            ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+            ("xsi:schemaLocation", MODS_SCHEMA),
+            ("version", "3.8"),
+        ],
+    );
+    for field in record.data_fields() {
+        match field.tag.as_str() {
+            "245" => title_info(&mut xml, field, None),
+            "246" => title_info(&mut xml, field, Some("alternative")),
+            "240" | "130" | "730" => title_info(&mut xml, field, Some("uniform")),
+            _ => {}
+        }
+    }
+    for field in record.data_fields() {
+        match field.tag.as_str() {
+            "100" | "110" | "111" => name(&mut xml, field, true),
+            "700" | "710" | "711" => name(&mut xml, field, false),
+            _ => {}
+        }
+    }
+    type_of_resource(&mut xml, record);
+    for field in record.data_fields().filter(|field| field.tag == "655") {
+        let authority = field.subfield("2").map(str::trim).unwrap_or_default();
+        let attributes = if authority.is_empty() {
+            vec![]
+        } else {
+            vec![("authority", authority)]
+        };
+        xml.element("genre", &attributes, &crosswalk::heading_text(field));
+    }
+    origin_info(&mut xml, record);
+    let languages = crosswalk::languages(record);
+    for language in &languages {
+        xml.start("language", &[]);
+        xml.element(
+            "languageTerm",
+            &[("type", "code"), ("authority", "iso639-2b")],
+            language,
+        );
+        xml.end("language");
+    }
+    physical_description(&mut xml, record);
+    for field in record.data_fields() {
+        let text = || subfields_text(field, "a").unwrap_or_default();
+        match field.tag.as_str() {
+            "520" => xml.element("abstract", &[], &text()),
+            "505" => xml.element("tableOfContents", &[], &text()),
+            "500" => xml.element("note", &[], &text()),
+            "504" => xml.element("note", &[("type", "bibliography")], &text()),
+            _ => {}
+        }
+    }
+    for field in record.data_fields() {
+        if ["600", "610", "611", "630", "650", "651"].contains(&field.tag.as_str()) {
+            subject(&mut xml, field);
+        }
+    }
+    for field in record.data_fields() {
+        let (authority, codes) = match field.tag.as_str() {
+            "050" => ("lcc", "ab"),
+            "060" => ("nlm", "ab"),
+            "080" => ("udc", "a"),
+            "082" => ("ddc", "a"),
+            _ => continue,
+        };
+        let edition = field.subfield("2").map(str::trim).unwrap_or_default();
+        let mut attributes = vec![("authority", authority)];
+        if field.tag == "082" && !edition.is_empty() {
+            attributes.push(("edition", edition));
+        }
+        let classification = subfields_text(field, codes).unwrap_or_default();
+        xml.element("classification", &attributes, &classification);
+    }
+    for field in record.data_fields().filter(|field| field.tag == "490") {
+        xml.start("relatedItem", &[("type", "series")]);
+        xml.start("titleInfo", &[]);
+        xml.element(
+            "title",
+            &[],
+            &subfields_text(field, "a").unwrap_or_default(),
+        );
+        xml.element(
+            "partNumber",
+            &[],
+            &subfields_text(field, "v").unwrap_or_default(),
+        );
+        xml.end("titleInfo");
+        xml.end("relatedItem");
+    }
+    for field in record.data_fields() {
+        let kind = match field.tag.as_str() {
+            "020" => Some("isbn"),
+            "022" => Some("issn"),
+            "024" => other_identifier_type(field),
+            "028" => Some("music publisher"),
+            _ => continue,
+        };
+        let identifier = field
+            .subfield("a")
+            .map(|data| punctuation::strip(data.trim(), false))
+            .unwrap_or_default();
+        let attributes = kind.map(|kind| vec![("type", kind)]).unwrap_or_default();
+        xml.element("identifier", &attributes, identifier);
+    }
+    for field in record.data_fields().filter(|field| field.tag == "856") {
+        if let Some(url) = field.subfield("u").map(str::trim) {
+            xml.start("location", &[]);
+            let note = field.subfield("z").map(str::trim).unwrap_or_default();
+            let attributes = if note.is_empty() {
+                vec![]
+            } else {
+                vec![("note", note)]
+            };
+            xml.element("url", &attributes, url);
+            xml.end("location");
+        }
+    }
+    record_info(&mut xml, record);
+    xml.end("mods");
+    xml.finish()
+}
+
+fn title_info(xml: &mut XmlWriter, field: &DataFieldWrapper, kind: Option<&str>) {
+    let attributes = kind.map(|kind| vec![("type", kind)]).unwrap_or_default();
+    let mut title = subfields_text(field, "a").unwrap_or_default();
+    // The number of nonfiling characters is the second indicator of 245 and 240, and the first
+    // one of 130 and 730.
+    let nonfiling = match field.tag.as_str() {
+        "245" | "240" => field.indicator.chars().nth(1),
+        "130" | "730" => field.indicator.chars().next(),
+        _ => None,
+    }
+    .and_then(|count| count.to_digit(10))
+    .unwrap_or(0) as usize;
+    xml.start("titleInfo", &attributes);
+    if nonfiling > 0 && title.chars().count() > nonfiling {
+        let non_sort = title.chars().take(nonfiling).collect::<String>();
+        xml.element("nonSort", &[], &non_sort);
+        title = title.chars().skip(nonfiling).collect();
+    }
+    xml.element("title", &[], &title);
+    xml.element(
+        "subTitle",
+        &[],
+        &subfields_text(field, "b").unwrap_or_default(),
+    );
+    xml.element(
+        "partNumber",
+        &[],
+        &subfields_text(field, "n").unwrap_or_default(),
+    );
+    xml.element(
+        "partName",
+        &[],
+        &subfields_text(field, "p").unwrap_or_default(),
+    );
+    xml.end("titleInfo");
+}
+
+/// The type of a 024 identifier from the first indicator, or from `$2` when the source is
+/// given there. Unspecified sources have no type.
+fn other_identifier_type(field: &DataFieldWrapper) -> Option<&str> {
+    match field.indicator.chars().next() {
+        Some('0') => Some("isrc"),
+        Some('1') => Some("upc"),
+        Some('2') => Some("ismn"),
+        Some('3') => Some("ean"),
+        Some('4') => Some("sici"),
+        Some('7') => field
+            .subfield("2")
+            .map(str::trim)
+            .filter(|source| !source.is_empty()),
+        _ => None,
+    }
+}
+
+/// A name with its roles: the relator terms of `$e` and the relator codes of `$4`.
+fn name(xml: &mut XmlWriter, field: &DataFieldWrapper, primary: bool) {
+    let (kind, codes) = match &field.tag[1..] {
+        "00" => ("personal", "abcq"),
+        "10" => ("corporate", "abcdn"),
+        _ => ("conference", "acdnq"),
+    };
+    let mut attributes = vec![("type", kind)];
+    if primary {
+        attributes.push(("usage", "primary"));
+    }
+    xml.start("name", &attributes);
+    xml.element(
+        "namePart",
+        &[],
+        &subfields_text(field, codes).unwrap_or_default(),
+    );
+    if kind == "personal" {
+        xml.element(
+            "namePart",
+            &[("type", "date")],
+            &subfields_text(field, "d").unwrap_or_default(),
+        );
+    }
+    for subfield in &field.subfields {
+        let (role_type, role) = match subfield.tag.as_str() {
+            "e" => ("text", punctuation::strip(subfield.data.trim(), false)),
+            "4" => ("code", subfield.data.trim()),
+            _ => continue,
+        };
+        if role.is_empty() {
+            continue;
+        }
+        xml.start("role", &[]);
+        xml.element(
+            "roleTerm",
+            &[("type", role_type), ("authority", "marcrelator")],
+            role,
+        );
+        xml.end("role");
+    }
+    xml.end("name");
+}
+
+/// The type of resource from leader/06, flagged as a manuscript or a collection from leader/06
+/// and /07.
+fn type_of_resource(xml: &mut XmlWriter, record: &RecordWrapper) {
+    let record_type = record.leader.record_type();
+    let type_of_resource = match record_type {
+        RecordTypeWrapper::LanguageMaterial | RecordTypeWrapper::ManuscriptLanguageMaterial => {
+            "text"
+        }
+        RecordTypeWrapper::CartographicMaterial
+        | RecordTypeWrapper::ManuscriptCartographicMaterial => "cartographic",
+        RecordTypeWrapper::NotatedMusic | RecordTypeWrapper::ManuscriptNotatedMusic => {
+            "notated music"
+        }
+        RecordTypeWrapper::NonmusicalSoundRecording => "sound recording-nonmusical",
+        RecordTypeWrapper::MusicalSoundRecording => "sound recording-musical",
+        RecordTypeWrapper::TwoDimensionalNonprojectableGraphic => "still image",
+        RecordTypeWrapper::ProjectedMedium => "moving image",
+        RecordTypeWrapper::ThreeDimensionalArtifact => "three dimensional object",
+        RecordTypeWrapper::ComputerFile => "software, multimedia",
+        RecordTypeWrapper::Kit | RecordTypeWrapper::MixedMaterials => "mixed material",
+        _ => return,
+    };
+    let mut attributes = Vec::new();
+    if *record.leader.bibliographic_level() == BibliographicalLevelWrapper::Collection {
+        attributes.push(("collection", "yes"));
+    }
+    if matches!(
+        record_type,
+        RecordTypeWrapper::ManuscriptLanguageMaterial
+            | RecordTypeWrapper::ManuscriptCartographicMaterial
+            | RecordTypeWrapper::ManuscriptNotatedMusic
+    ) {
+        attributes.push(("manuscript", "yes"));
+    }
+    xml.element("typeOfResource", &attributes, type_of_resource);
+}
+
+/// Place, publisher and dates from 260/264 and 008, edition from 250 and issuance from
+/// leader/07.
+fn origin_info(xml: &mut XmlWriter, record: &RecordWrapper) {
+    let fixed = record
+        .control_field("008")
+        .map(|data| data.chars().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let fixed_value = |start: usize, length: usize| {
+        let value = fixed.iter().skip(start).take(length).collect::<String>();
+        value.trim_end_matches([' ', '|']).to_string()
+    };
+    let publication = crosswalk::publication_field(record);
+    let subfield = |code| {
+        publication
+            .and_then(|field| subfields_text(field, code))
+            .unwrap_or_default()
+    };
+
+    xml.start("originInfo", &[]);
+    let country = fixed_value(15, 3);
+    if !country.is_empty() {
+        xml.start("place", &[]);
+        xml.element(
+            "placeTerm",
+            &[("type", "code"), ("authority", "marccountry")],
+            &country,
+        );
+        xml.end("place");
+    }
+    let place = subfield("a");
+    if !place.is_empty() {
+        xml.start("place", &[]);
+        xml.element("placeTerm", &[("type", "text")], &place);
+        xml.end("place");
+    }
+    xml.element("publisher", &[], &subfield("b"));
+    xml.element("dateIssued", &[], &subfield("c"));
+    let date = fixed_value(7, 4);
+    if date.len() == 4 {
+        xml.element(
+            "dateIssued",
+            &[("encoding", "marc"), ("keyDate", "yes")],
+            &date,
+        );
+    }
+    if let Some(edition) = record.data_fields().find(|field| field.tag == "250") {
+        xml.element(
+            "edition",
+            &[],
+            &subfields_text(edition, "ab").unwrap_or_default(),
+        );
+    }
+    let issuance = match record.leader.bibliographic_level() {
+        BibliographicalLevelWrapper::Monograph
+        | BibliographicalLevelWrapper::MonographicComponentPart => "monographic",
+        BibliographicalLevelWrapper::Serial | BibliographicalLevelWrapper::SerialComponentPart => {
+            "serial"
+        }
+        BibliographicalLevelWrapper::IntegratingResource => "integrating resource",
+        BibliographicalLevelWrapper::Collection | BibliographicalLevelWrapper::Subunit => {
+            "single unit"
+        }
+        BibliographicalLevelWrapper::Unknown => "",
+    };
+    xml.element("issuance", &[], issuance);
+    xml.end("originInfo");
+}
+
+fn physical_description(xml: &mut XmlWriter, record: &RecordWrapper) {
+    let extents = record
+        .data_fields()
+        .filter(|field| field.tag == "300")
+        .filter_map(|field| subfields_text(field, "abce3"))
+        .collect::<Vec<_>>();
+    if extents.is_empty() {
+        return;
+    }
+    xml.start("physicalDescription", &[]);
+    for extent in &extents {
+        xml.element("extent", &[], extent);
+    }
+    xml.end("physicalDescription");
+}
+
+/// A subject with one child element per subfield, e.g. `topic` for `$x` and `geographic` for
+/// `$z`. The thesaurus comes from the second indicator or `$2`.
+fn subject(xml: &mut XmlWriter, field: &DataFieldWrapper) {
+    let authority = match field.indicator.chars().nth(1) {
+        Some('0') => "lcsh",
+        Some('1') => "lcshac",
+        Some('2') => "mesh",
+        Some('3') => "nal",
+        Some('5') => "cash",
+        Some('6') => "rvm",
+        Some('7') => field.subfield("2").map(str::trim).unwrap_or_default(),
+        _ => "",
+    };
+    let attributes = if authority.is_empty() {
+        vec![]
+    } else {
+        vec![("authority", authority)]
+    };
+    xml.start("subject", &attributes);
+    match field.tag.as_str() {
+        "600" | "610" | "611" => {
+            let (kind, codes) = match field.tag.as_str() {
+                "600" => ("personal", "abcdq"),
+                "610" => ("corporate", "abn"),
+                _ => ("conference", "acdnq"),
+            };
+            xml.start("name", &[("type", kind)]);
+            xml.element(
+                "namePart",
+                &[],
+                &subfields_text(field, codes).unwrap_or_default(),
+            );
+            xml.end("name");
+        }
+        "630" => {
+            xml.start("titleInfo", &[]);
+            xml.element(
+                "title",
+                &[],
+                &subfields_text(field, "adfklmnoprst").unwrap_or_default(),
+            );
+            xml.end("titleInfo");
+        }
+        "650" => xml.element(
+            "topic",
+            &[],
+            &subfields_text(field, "abcd").unwrap_or_default(),
+        ),
+        _ => xml.element(
+            "geographic",
+            &[],
+            &subfields_text(field, "a").unwrap_or_default(),
+        ),
+    }
+    for subfield in &field.subfields {
+        let element = match subfield.tag.as_str() {
+            "v" => "genre",
+            "x" => "topic",
+            "y" => "temporal",
+            "z" => "geographic",
+            _ => continue,
+        };
+        xml.element(
+            element,
+            &[],
+            punctuation::strip(subfield.data.trim(), false),
+        );
+    }
+    xml.end("subject");
+}
+
+fn record_info(xml: &mut XmlWriter, record: &RecordWrapper) {
+    xml.start("recordInfo", &[]);
+    if let Some(source) = record.data_fields().find(|field| field.tag == "040") {
+        xml.element(
+            "recordContentSource",
+            &[("authority", "marcorg")],
+            &subfields_text(source, "a").unwrap_or_default(),
+        );
+    }
+    if let Some(fixed) = record.control_field("008") {
+        let created = fixed.chars().take(6).collect::<String>();
+        xml.element(
+            "recordCreationDate",
+            &[("encoding", "marc")],
+            created.trim(),
+        );
+    }
+    if let Some(changed) = record.control_field("005") {
+        xml.element(
+            "recordChangeDate",
+            &[("encoding", "iso8601")],
+            changed.trim(),
+        );
+    }
+    if let Some(identifier) = record.control_field("001") {
+        let source = record
+            .control_field("003")
+            .map(str::trim)
+            .unwrap_or_default();
+        let attributes = if source.is_empty() {
+            vec![]
+        } else {
+            vec![("source", source)]
+        };
+        xml.element("recordIdentifier", &attributes, identifier.trim());
+    }
+    xml.element(
+        "recordOrigin",
+        &[],
+        "Converted from MARC 21 to MODS version 3.8",
+    );
+    xml.end("recordInfo");
+}
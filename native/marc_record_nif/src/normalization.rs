@@ -0,0 +1,36 @@
+/// Unicode normalization of the data of the records. Records from different sources mix
+/// precomposed (`é`, U+00E9) and decomposed (`e` followed by U+0301) characters, which look the
+/// same but do not compare equal.
+///
+/// Info about the normalization forms can be found here: https://unicode.org/reports/tr15/
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::options::Normalization;
+use crate::FieldWrapper;
+
+/// Normalizes the data of the control fields and of the subfields.
+pub(crate) fn normalize_fields(fields: &mut [FieldWrapper], normalization: Normalization) {
+    for field in fields {
+        match field {
+            FieldWrapper::Control(control) => {
+                control.data = normalize(&control.data, normalization);
+            }
+            FieldWrapper::Data(data) => {
+                for subfield in &mut data.subfields {
+                    subfield.data = normalize(&subfield.data, normalization);
+                }
+            }
+        }
+    }
+}
+
+/// The text in the given form. The search key is the text without its diacritics, e.g.
+/// `Economie` for `Économie`: the text is decomposed and the combining marks removed.
+pub fn normalize(text: &str, normalization: Normalization) -> String {
+    match normalization {
+        Normalization::Nfc => text.nfc().collect(),
+        Normalization::Nfd => text.nfd().collect(),
+        Normalization::SearchKey => text.nfd().filter(|c| !is_combining_mark(*c)).collect(),
+    }
+}
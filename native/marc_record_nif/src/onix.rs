@@ -0,0 +1,562 @@
+/// Reads ONIX for Books 3.0 messages, the product metadata sent by publishers and
+/// distributors, and converts each product into a MARC 21 bibliographic record. Both the
+/// reference tags (`<Product>`) and the short tags (`<product>`) are understood.
+///
+/// The records are written in ISO 2709 and read back with `marc-record`, so they can be used
+/// with the encoders of this crate as well as with any MARC writer. As ONIX has no ISBD
+/// punctuation, the records are flagged as such (leader/18 = `c`).
+///
+/// Info about the format can be found here: https://www.editeur.org/83/Overview/
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::writer;
+use crate::{ControlFieldWrapper, DataFieldWrapper, FieldWrapper, SubfieldWrapper};
+
+/// Monograph of language material, in UTF-8, with an abbreviated encoding level.
+const LEADER: &[u8; 24] = b"00000nam a22000003c 4500";
+
+/// Converts every product of an ONIX message to a MARC 21 record in ISO 2709.
+pub fn to_iso2709(xml: &[u8]) -> Result<Vec<u8>, String> {
+    let message = parse(xml)?;
+    // The records are entered on the day the message was sent.
+    let sent = message
+        .find(&["Header", "SentDateTime"])
+        .map_or("", |date| date.text.trim());
+    let mut data = Vec::new();
+    for product in message.children("Product") {
+        data.extend(writer::write_record(
+            LEADER,
+            &product_fields(product, sent),
+        )?);
+    }
+    Ok(data)
+}
+
+/// An element of the message, with its text and its child elements.
+struct Element {
+    name: String,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// The text of a child element, if present and not empty.
+    fn value(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|child| child.text.trim())
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Finds a descendant by following the path of element names.
+    fn find(&self, path: &[&str]) -> Option<&Element> {
+        path.iter()
+            .try_fold(self, |element, name| element.child(name))
+    }
+}
+
+/// Short tags and their reference names, for the elements read here.
+const SHORT_TAGS: &[(&str, &str)] = &[
+    ("ONIXmessage", "ONIXMessage"),
+    ("header", "Header"),
+    ("x307", "SentDateTime"),
+    ("product", "Product"),
+    ("a001", "RecordReference"),
+    ("productidentifier", "ProductIdentifier"),
+    ("b221", "ProductIDType"),
+    ("b244", "IDValue"),
+    ("descriptivedetail", "DescriptiveDetail"),
+    ("b012", "ProductForm"),
+    ("b333", "ProductFormDetail"),
+    ("titledetail", "TitleDetail"),
+    ("b202", "TitleType"),
+    ("titleelement", "TitleElement"),
+    ("x409", "TitleElementLevel"),
+    ("b203", "TitleText"),
+    ("b030", "TitlePrefix"),
+    ("b031", "TitleWithoutPrefix"),
+    ("b029", "Subtitle"),
+    ("contributor", "Contributor"),
+    ("b034", "SequenceNumber"),
+    ("b035", "ContributorRole"),
+    ("b036", "PersonName"),
+    ("b037", "PersonNameInverted"),
+    ("b039", "NamesBeforeKey"),
+    ("b040", "KeyNames"),
+    ("b047", "CorporateName"),
+    ("editionstatement", "EditionStatement"),
+    ("language", "Language"),
+    ("b253", "LanguageRole"),
+    ("b252", "LanguageCode"),
+    ("extent", "Extent"),
+    ("b218", "ExtentType"),
+    ("b219", "ExtentValue"),
+    ("b220", "ExtentUnit"),
+    ("subject", "Subject"),
+    ("x425", "MainSubject"),
+    ("b067", "SubjectSchemeIdentifier"),
+    ("b069", "SubjectCode"),
+    ("b070", "SubjectHeadingText"),
+    ("collateraldetail", "CollateralDetail"),
+    ("textcontent", "TextContent"),
+    ("x426", "TextType"),
+    ("d104", "Text"),
+    ("supportingresource", "SupportingResource"),
+    ("x436", "ResourceContentType"),
+    ("resourceversion", "ResourceVersion"),
+    ("x435", "ResourceLink"),
+    ("publishingdetail", "PublishingDetail"),
+    ("publisher", "Publisher"),
+    ("b081", "PublisherName"),
+    ("b209", "CityOfPublication"),
+    ("publishingdate", "PublishingDate"),
+    ("x448", "PublishingDateRole"),
+    ("b306", "Date"),
+    ("website", "Website"),
+    ("b295", "WebsiteLink"),
+];
+
+fn reference_name(name: &str) -> String {
+    SHORT_TAGS
+        .iter()
+        .find(|(short, _)| *short == name)
+        .map_or(name, |(_, reference)| reference)
+        .to_string()
+}
+
+fn parse(xml: &[u8]) -> Result<Element, String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut stack = vec![Element {
+        name: String::new(),
+        text: String::new(),
+        children: Vec::new(),
+    }];
+    let mut buffer = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buffer)
+            .map_err(|error| format!("{} at byte {}", error, reader.buffer_position()))?;
+        let is_empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(start) | Event::Empty(start) => {
+                let name = String::from_utf8_lossy(start.local_name().as_ref()).to_string();
+                let element = Element {
+                    name: reference_name(&name),
+                    text: String::new(),
+                    children: Vec::new(),
+                };
+                if is_empty {
+                    stack.last_mut().unwrap().children.push(element);
+                } else {
+                    stack.push(element);
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Err("unbalanced end tag".to_string()),
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|error| error.to_string())?;
+                stack.last_mut().unwrap().text.push_str(&text);
+            }
+            Event::CData(data) => {
+                let text = String::from_utf8_lossy(&data);
+                stack.last_mut().unwrap().text.push_str(&text);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+    let mut document = stack.pop().unwrap();
+    if !stack.is_empty() {
+        return Err(format!("element {} is not closed", document.name));
+    }
+    document
+        .children
+        .pop()
+        .filter(|root| root.name == "ONIXMessage")
+        .ok_or_else(|| "the document is not an ONIX message".to_string())
+}
+
+fn control_field(tag: &str, data: String) -> FieldWrapper {
+    FieldWrapper::Control(ControlFieldWrapper {
+        tag: tag.to_string(),
+        data,
+    })
+}
+
+fn data_field(tag: &str, indicator: &str, subfields: Vec<(&str, String)>) -> FieldWrapper {
+    FieldWrapper::Data(DataFieldWrapper {
+        tag: tag.to_string(),
+        indicator: indicator.to_string(),
+        subfields: subfields
+            .into_iter()
+            .map(|(code, data)| SubfieldWrapper {
+                tag: code.to_string(),
+                data,
+                source: None,
+            })
+            .collect(),
+        linked: None,
+    })
+}
+
+/// The fields of the record of a product, ordered by tag.
+fn product_fields(product: &Element, sent: &str) -> Vec<FieldWrapper> {
+    let descriptive = product.child("DescriptiveDetail");
+    let collateral = product.child("CollateralDetail");
+    let publishing = product.child("PublishingDetail");
+    let product_form = descriptive.and_then(|detail| detail.value("ProductForm"));
+    // Digital products have a form code starting with `E`, or `AJ` for downloadable audio.
+    let is_digital = product_form.is_some_and(|form| form.starts_with('E') || form == "AJ");
+    let publication_date = publishing
+        .into_iter()
+        .flat_map(|detail| detail.children("PublishingDate"))
+        .find(|date| date.value("PublishingDateRole") == Some("01"))
+        .and_then(|date| date.value("Date"))
+        .unwrap_or_default();
+    let language = descriptive
+        .into_iter()
+        .flat_map(|detail| detail.children("Language"))
+        .find(|language| language.value("LanguageRole") == Some("01"))
+        .and_then(|language| language.value("LanguageCode"));
+
+    let mut fields = Vec::new();
+    if let Some(reference) = product.value("RecordReference") {
+        fields.push(control_field("001", reference.to_string()));
+    }
+    fields.push(control_field(
+        "008",
+        fixed_length_data(sent, publication_date, language, is_digital),
+    ));
+    fields.extend(identifier_fields(product, descriptive));
+    let contributors = contributor_fields(descriptive);
+    let has_main_entry = contributors
+        .iter()
+        .any(|field| matches!(field, FieldWrapper::Data(data) if data.tag.starts_with('1')));
+    if let Some(title) = descriptive.and_then(|detail| title_field(detail, has_main_entry)) {
+        fields.push(title);
+    }
+    if let Some(edition) = descriptive.and_then(|detail| detail.value("EditionStatement")) {
+        fields.push(data_field("250", "  ", vec![("a", edition.to_string())]));
+    }
+    if let Some(publishing) = publishing {
+        let mut subfields = Vec::new();
+        if let Some(city) = publishing.value("CityOfPublication") {
+            subfields.push(("a", city.to_string()));
+        }
+        if let Some(name) = publishing.find(&["Publisher", "PublisherName"]) {
+            subfields.push(("b", name.text.trim().to_string()));
+        }
+        if let Some(year) = publication_date.get(..4) {
+            subfields.push(("c", year.to_string()));
+        }
+        if !subfields.is_empty() {
+            fields.push(data_field("264", " 1", subfields));
+        }
+    }
+    if let Some(extent) = descriptive.and_then(|detail| extent_text(detail, is_digital)) {
+        fields.push(data_field("300", "  ", vec![("a", extent)]));
+    }
+    for text in collateral
+        .into_iter()
+        .flat_map(|detail| detail.children("TextContent"))
+        .filter(|text| matches!(text.value("TextType"), Some("02" | "03")))
+        .filter_map(|text| text.value("Text"))
+    {
+        fields.push(data_field("520", "  ", vec![("a", strip_markup(text))]));
+    }
+    fields.extend(subject_fields(descriptive));
+    if is_digital {
+        fields.push(data_field(
+            "655",
+            " 0",
+            vec![("a", "Electronic books".to_string())],
+        ));
+    }
+    fields.extend(contributors);
+    fields.extend(link_fields(product, collateral, publishing));
+    fields.sort_by_key(|field| match field {
+        FieldWrapper::Control(control) => control.tag.clone(),
+        FieldWrapper::Data(data) => data.tag.clone(),
+    });
+    fields
+}
+
+/// Builds a 008 field for books from the date the message was sent, the publication date (both
+/// starting with YYYYMMDD) and the language of the text. Position 23 tells whether the book is
+/// online.
+fn fixed_length_data(sent: &str, date: &str, language: Option<&str>, is_digital: bool) -> String {
+    let date_entered = sent.get(2..8).unwrap_or("      ");
+    let (date_type, year) = match date.get(..4) {
+        Some(year) => ('s', year),
+        None => ('n', "uuuu"),
+    };
+    let form = if is_digital { 'o' } else { ' ' };
+    format!(
+        "{}{}{}    xx {:5}{}{:11}{:3} d",
+        date_entered,
+        date_type,
+        year,
+        "",
+        form,
+        "",
+        language.unwrap_or("und")
+    )
+}
+
+fn identifier_fields(product: &Element, descriptive: Option<&Element>) -> Vec<FieldWrapper> {
+    // The format of e-books qualifies their ISBN, e.g. `(EPUB)`.
+    let qualifier = descriptive
+        .into_iter()
+        .flat_map(|detail| detail.children("ProductFormDetail"))
+        .find_map(|detail| match detail.text.trim() {
+            "E101" => Some("EPUB"),
+            "E107" => Some("PDF"),
+            "E116" => Some("Kindle"),
+            _ => None,
+        });
+    product
+        .children("ProductIdentifier")
+        .filter_map(|identifier| {
+            let value = identifier.value("IDValue")?.to_string();
+            match identifier.value("ProductIDType")? {
+                "02" | "15" => {
+                    let mut subfields = vec![("a", value)];
+                    if let Some(qualifier) = qualifier {
+                        subfields.push(("q", qualifier.to_string()));
+                    }
+                    Some(data_field("020", "  ", subfields))
+                }
+                "03" => Some(data_field("024", "3 ", vec![("a", value)])),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The distinctive title (title type `01`) at the level of the product (`01`).
+fn title_field(descriptive: &Element, has_main_entry: bool) -> Option<FieldWrapper> {
+    let element = descriptive
+        .children("TitleDetail")
+        .find(|detail| detail.value("TitleType") == Some("01"))?
+        .children("TitleElement")
+        .find(|element| element.value("TitleElementLevel") == Some("01"))?;
+    let (title, nonfiling) = match element.value("TitleWithoutPrefix") {
+        Some(title) => match element.value("TitlePrefix") {
+            // The prefix and the space following it are nonfiling characters.
+            Some(prefix) => (
+                format!("{} {}", prefix, title),
+                (prefix.chars().count() + 1).min(9),
+            ),
+            None => (title.to_string(), 0),
+        },
+        None => (element.value("TitleText")?.to_string(), 0),
+    };
+    let mut subfields = vec![("a", title)];
+    if let Some(subtitle) = element.value("Subtitle") {
+        subfields.push(("b", subtitle.to_string()));
+    }
+    let indicator = format!("{}{}", if has_main_entry { '1' } else { '0' }, nonfiling);
+    Some(data_field("245", &indicator, subfields))
+}
+
+/// Contributor roles and the matching MARC relator codes and terms.
+const ROLES: &[(&str, &str, &str)] = &[
+    ("A01", "aut", "author"),
+    ("A06", "cmp", "composer"),
+    ("A08", "pht", "photographer"),
+    ("A12", "ill", "illustrator"),
+    ("A15", "wpr", "writer of preface"),
+    ("A23", "wfw", "writer of foreword"),
+    ("A24", "aui", "author of introduction"),
+    ("B01", "edt", "editor"),
+    ("B06", "trl", "translator"),
+    ("E07", "nrt", "narrator"),
+];
+
+/// The first author is the main entry (100 or 110), the other contributors are added entries
+/// (700 or 710), in the order of their sequence numbers.
+fn contributor_fields(descriptive: Option<&Element>) -> Vec<FieldWrapper> {
+    let mut contributors = descriptive
+        .into_iter()
+        .flat_map(|detail| detail.children("Contributor"))
+        .collect::<Vec<_>>();
+    contributors.sort_by_key(|contributor| {
+        contributor
+            .value("SequenceNumber")
+            .and_then(|number| number.parse::<u32>().ok())
+            .unwrap_or(u32::MAX)
+    });
+    let mut has_main_entry = false;
+    contributors
+        .into_iter()
+        .filter_map(|contributor| {
+            let (is_personal, name) = contributor_name(contributor)?;
+            let role = contributor
+                .value("ContributorRole")
+                .and_then(|code| ROLES.iter().find(|(role, _, _)| *role == code));
+            let is_main_entry = !has_main_entry && role.is_some_and(|(code, _, _)| *code == "A01");
+            has_main_entry |= is_main_entry;
+            let tag = match (is_main_entry, is_personal) {
+                (true, true) => "100",
+                (true, false) => "110",
+                (false, true) => "700",
+                (false, false) => "710",
+            };
+            let indicator = if is_personal { "1 " } else { "2 " };
+            let mut subfields = vec![("a", name)];
+            if let Some((_, code, term)) = role {
+                subfields.push(("e", term.to_string()));
+                subfields.push(("4", code.to_string()));
+            }
+            Some(data_field(tag, indicator, subfields))
+        })
+        .collect()
+}
+
+/// Whether the contributor is a person, and the name in the inverted form of MARC 21.
+fn contributor_name(contributor: &Element) -> Option<(bool, String)> {
+    if let Some(name) = contributor.value("PersonNameInverted") {
+        return Some((true, name.to_string()));
+    }
+    if let Some(key_names) = contributor.value("KeyNames") {
+        let name = match contributor.value("NamesBeforeKey") {
+            Some(before) => format!("{}, {}", key_names, before),
+            None => key_names.to_string(),
+        };
+        return Some((true, name));
+    }
+    if let Some(name) = contributor.value("CorporateName") {
+        return Some((false, name.to_string()));
+    }
+    contributor
+        .value("PersonName")
+        .map(|name| (true, name.to_string()))
+}
+
+/// The page count (extent type `00` or `08`, in pages).
+fn extent_text(descriptive: &Element, is_digital: bool) -> Option<String> {
+    let pages = descriptive
+        .children("Extent")
+        .filter(|extent| matches!(extent.value("ExtentType"), Some("00" | "08" | "10")))
+        .find(|extent| extent.value("ExtentUnit") == Some("03"))?
+        .value("ExtentValue")?;
+    Some(if is_digital {
+        format!("1 online resource ({} pages)", pages)
+    } else {
+        format!("{} pages", pages)
+    })
+}
+
+/// Subject schemes: LC subject headings become 650 with second indicator `0`, BISAC and Thema
+/// 650 with the source in `$2`, Dewey numbers 082 and keywords 653.
+fn subject_fields(descriptive: Option<&Element>) -> Vec<FieldWrapper> {
+    let subjects = descriptive
+        .into_iter()
+        .flat_map(|detail| detail.children("Subject"));
+    let mut fields = Vec::new();
+    for subject in subjects {
+        let heading = subject.value("SubjectHeadingText");
+        let code = subject.value("SubjectCode");
+        match subject.value("SubjectSchemeIdentifier") {
+            Some("01") => {
+                if let Some(code) = code {
+                    fields.push(data_field("082", "04", vec![("a", code.to_string())]));
+                }
+            }
+            Some("04") => {
+                if let Some(heading) = heading {
+                    fields.push(data_field("650", " 0", vec![("a", heading.to_string())]));
+                }
+            }
+            Some(scheme @ ("10" | "93")) => {
+                let source = if scheme == "10" { "bisacsh" } else { "thema" };
+                if let Some(term) = heading.or(code) {
+                    let mut subfields = vec![("a", term.to_string())];
+                    if let (Some(code), Some(_)) = (code, heading) {
+                        subfields.push(("0", code.to_string()));
+                    }
+                    subfields.push(("2", source.to_string()));
+                    fields.push(data_field("650", " 7", subfields));
+                }
+            }
+            Some("20") => {
+                let keywords = heading.unwrap_or_default().split(';').map(str::trim);
+                for keyword in keywords.filter(|keyword| !keyword.is_empty()) {
+                    fields.push(data_field("653", "  ", vec![("a", keyword.to_string())]));
+                }
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// Links to the product (websites) and to its resources, such as the cover image.
+fn link_fields(
+    product: &Element,
+    collateral: Option<&Element>,
+    publishing: Option<&Element>,
+) -> Vec<FieldWrapper> {
+    let mut fields = Vec::new();
+    let resources = collateral
+        .into_iter()
+        .flat_map(|detail| detail.children("SupportingResource"));
+    for resource in resources {
+        let material = match resource.value("ResourceContentType") {
+            Some("01") => "Cover image",
+            Some("15") => "Sample content",
+            _ => "Related resource",
+        };
+        for link in resource
+            .children("ResourceVersion")
+            .filter_map(|version| version.value("ResourceLink"))
+        {
+            fields.push(data_field(
+                "856",
+                "42",
+                vec![("3", material.to_string()), ("u", link.to_string())],
+            ));
+        }
+    }
+    let websites = publishing
+        .into_iter()
+        .flat_map(|detail| detail.find(&["Publisher"]))
+        .chain(std::iter::once(product))
+        .flat_map(|element| element.children("Website"));
+    for link in websites.filter_map(|website| website.value("WebsiteLink")) {
+        fields.push(data_field("856", "40", vec![("u", link.to_string())]));
+    }
+    fields
+}
+
+/// Descriptions may be XHTML; the tags are removed and the entities were decoded by the reader.
+fn strip_markup(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                stripped.push(' ');
+            }
+            _ if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,298 @@
+/// Options accepted by the NIFs, given from Elixir as keyword lists, e.g.
+/// `[flavor: :unimarc, strip_punctuation: true, normalization: :nfc, parallel: true]`.
+/// Unknown options raise an `ArgumentError`.
+use rustler::{Atom, Decoder, Error, NifResult, Term};
+
+mod atoms {
+    rustler::atoms! {
+        flavor,
+        marc21,
+        unimarc,
+        strip_punctuation,
+        normalization,
+        nfc,
+        nfd,
+        search_key,
+        link_fields,
+        strategies,
+        oclc,
+        isbn,
+        title_author,
+        threshold,
+        policies,
+        keep,
+        replace,
+        append,
+        union,
+        key,
+        control_number,
+        system_number,
+        parallel,
+    }
+}
+
+/// The family of formats the records follow, which changes the meaning of the leader values
+/// and of the tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Flavor {
+    #[default]
+    Marc21,
+    Unimarc,
+}
+
+impl<'a> Decoder<'a> for Flavor {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let flavor: Atom = term.decode()?;
+        if flavor == atoms::marc21() {
+            Ok(Flavor::Marc21)
+        } else if flavor == atoms::unimarc() {
+            Ok(Flavor::Unimarc)
+        } else {
+            Err(Error::BadArg)
+        }
+    }
+}
+
+/// The Unicode form the data is converted to. `SearchKey` also removes the diacritics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    Nfc,
+    Nfd,
+    SearchKey,
+}
+
+impl<'a> Decoder<'a> for Normalization {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let normalization: Atom = term.decode()?;
+        if normalization == atoms::nfc() {
+            Ok(Normalization::Nfc)
+        } else if normalization == atoms::nfd() {
+            Ok(Normalization::Nfd)
+        } else if normalization == atoms::search_key() {
+            Ok(Normalization::SearchKey)
+        } else {
+            Err(Error::BadArg)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub flavor: Flavor,
+    /// Removes the ISBD punctuation ending the subfield data, unless the leader tells that the
+    /// record has none (leader/18 `c` or `n`).
+    pub strip_punctuation: bool,
+    /// Converts the control field and subfield data to a Unicode normalization form. The data
+    /// is left as decoded when `None`.
+    pub normalization: Option<Normalization>,
+    /// Returns the 880 fields within the regular fields they are linked to by `$6`.
+    pub link_fields: bool,
+    /// Decodes the records on all the cores. Worth it for large binaries only.
+    pub parallel: bool,
+}
+
+impl<'a> Decoder<'a> for ParseOptions {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let mut options = ParseOptions::default();
+        for (key, value) in term.decode::<Vec<(Atom, Term<'a>)>>()? {
+            if key == atoms::flavor() {
+                options.flavor = value.decode()?;
+            } else if key == atoms::strip_punctuation() {
+                options.strip_punctuation = value.decode()?;
+            } else if key == atoms::normalization() {
+                options.normalization = Some(value.decode()?);
+            } else if key == atoms::link_fields() {
+                options.link_fields = value.decode()?;
+            } else if key == atoms::parallel() {
+                options.parallel = value.decode()?;
+            } else {
+                return Err(Error::BadArg);
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// A way to tell that two records describe the same resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// The same OCLC number (035).
+    Oclc,
+    /// A valid ISBN in common (020).
+    Isbn,
+    /// The same author and year, and similar titles.
+    TitleAuthor,
+}
+
+impl<'a> Decoder<'a> for Strategy {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let strategy: Atom = term.decode()?;
+        if strategy == atoms::oclc() {
+            Ok(Strategy::Oclc)
+        } else if strategy == atoms::isbn() {
+            Ok(Strategy::Isbn)
+        } else if strategy == atoms::title_author() {
+            Ok(Strategy::TitleAuthor)
+        } else {
+            Err(Error::BadArg)
+        }
+    }
+}
+
+/// Options of `dedupe`, e.g. `[strategies: [:oclc, :isbn], threshold: 0.85]`.
+#[derive(Debug, Clone)]
+pub struct DedupeOptions {
+    pub strategies: Vec<Strategy>,
+    /// The similarity (0.0 to 1.0) above which two titles match.
+    pub threshold: f64,
+}
+
+impl Default for DedupeOptions {
+    fn default() -> Self {
+        DedupeOptions {
+            strategies: vec![Strategy::Oclc, Strategy::Isbn, Strategy::TitleAuthor],
+            threshold: 0.9,
+        }
+    }
+}
+
+impl<'a> Decoder<'a> for DedupeOptions {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let mut options = DedupeOptions::default();
+        for (key, value) in term.decode::<Vec<(Atom, Term<'a>)>>()? {
+            if key == atoms::strategies() {
+                options.strategies = value.decode()?;
+            } else if key == atoms::threshold() {
+                options.threshold = value.decode()?;
+            } else {
+                return Err(Error::BadArg);
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// What becomes of the fields of a tag when a record is merged into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// The fields of the master record stay.
+    Keep,
+    /// The fields of the incoming record replace the ones of the master record, if any.
+    Replace,
+    /// The fields of the incoming record are added.
+    Append,
+    /// The fields of the incoming record are added unless the master record has them.
+    Union,
+}
+
+impl<'a> Decoder<'a> for Policy {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let policy: Atom = term.decode()?;
+        if policy == atoms::keep() {
+            Ok(Policy::Keep)
+        } else if policy == atoms::replace() {
+            Ok(Policy::Replace)
+        } else if policy == atoms::append() {
+            Ok(Policy::Append)
+        } else if policy == atoms::union() {
+            Ok(Policy::Union)
+        } else {
+            Err(Error::BadArg)
+        }
+    }
+}
+
+/// Options of `merge_records`, e.g. `[policies: %{"6XX" => :union, "020" => :append}]`. The
+/// policies are given by tag, `X` standing for any digit. They take precedence over the default
+/// ones: a default policy whose tags a given pattern covers is dropped, e.g. `6XX` drops the
+/// default `650`.
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    pub policies: Vec<(String, Policy)>,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            policies: vec![
+                ("001".to_string(), Policy::Keep),
+                ("003".to_string(), Policy::Keep),
+                ("650".to_string(), Policy::Union),
+                ("856".to_string(), Policy::Replace),
+                ("9XX".to_string(), Policy::Keep),
+            ],
+        }
+    }
+}
+
+impl<'a> Decoder<'a> for MergeOptions {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let mut options = MergeOptions::default();
+        for (key, value) in term.decode::<Vec<(Atom, Term<'a>)>>()? {
+            if key == atoms::policies() {
+                let policies: std::collections::HashMap<String, Policy> = value.decode()?;
+                for pattern in policies.keys() {
+                    let is_pattern = pattern.len() == 3
+                        && pattern.chars().all(|c| c.is_ascii_digit() || c == 'X');
+                    if !is_pattern {
+                        return Err(Error::BadArg);
+                    }
+                    let covers = |other: &str| {
+                        pattern
+                            .chars()
+                            .zip(other.chars())
+                            .all(|(p, o)| p == 'X' || p == o)
+                    };
+                    options.policies.retain(|(other, _)| !covers(other));
+                }
+                options.policies.extend(policies);
+            } else {
+                return Err(Error::BadArg);
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The field identifying the records of an update file in the base file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateKey {
+    /// The control number (001).
+    #[default]
+    ControlNumber,
+    /// The first system control number (035 `$a`), e.g. `(OCoLC)12345`.
+    SystemNumber,
+}
+
+impl<'a> Decoder<'a> for UpdateKey {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let key: Atom = term.decode()?;
+        if key == atoms::control_number() {
+            Ok(UpdateKey::ControlNumber)
+        } else if key == atoms::system_number() {
+            Ok(UpdateKey::SystemNumber)
+        } else {
+            Err(Error::BadArg)
+        }
+    }
+}
+
+/// Options of `apply_updates`, e.g. `[key: :system_number]`.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    pub key: UpdateKey,
+}
+
+impl<'a> Decoder<'a> for UpdateOptions {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let mut options = UpdateOptions::default();
+        for (key, value) in term.decode::<Vec<(Atom, Term<'a>)>>()? {
+            if key == atoms::key() {
+                options.key = value.decode()?;
+            } else {
+                return Err(Error::BadArg);
+            }
+        }
+        Ok(options)
+    }
+}
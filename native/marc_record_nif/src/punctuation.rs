@@ -0,0 +1,73 @@
+/// Removal of the ISBD punctuation which ends subfield data, e.g. `Livres numériques.` becomes
+/// `Livres numériques` and `Montréal :` becomes `Montréal`. The punctuation separates the
+/// elements of a description for display; once in separate subfields it gets in the way of
+/// display with other separators and of indexing.
+///
+/// Only the punctuation ending a subfield is removed. A final period is kept when it belongs to
+/// the data: after an initial (`Marron, Donald B.`), after an abbreviation (`etc.`) and in the
+/// fields where abbreviations are prescribed, such as the extent (`160 p.`).
+///
+/// Info about the punctuation can be found here:
+/// https://www.loc.gov/aba/pcc/documents/PCC-Guidelines-Minimally-Punctuated-MARC-Data.docx
+use crate::FieldWrapper;
+
+/// Edition (250), cartographic mathematical data (255) and physical description (300) are made
+/// of abbreviations, their final period is kept.
+const ABBREVIATED_TAGS: [&str; 3] = ["250", "255", "300"];
+
+/// Common abbreviations whose period must be kept at the end of a subfield.
+const ABBREVIATIONS: [&str; 19] = [
+    "etc", "al", "Inc", "Ltd", "Co", "Corp", "Jr", "Sr", "St", "Dr", "ed", "éd", "eds", "comp",
+    "trad", "ill", "p", "v", "vol",
+];
+
+/// Strips the data fields of a record. Control fields, the number and code fields (0XX), the
+/// electronic location (856) and the local fields (9XX) have no ISBD punctuation, like the
+/// control subfields (`$0`-`$9`).
+pub(crate) fn strip_fields(fields: &mut [FieldWrapper]) {
+    for field in fields {
+        let FieldWrapper::Data(field) = field else {
+            continue;
+        };
+        if field.tag.starts_with('0') || field.tag.starts_with('9') || field.tag == "856" {
+            continue;
+        }
+        let keep_period = keeps_period(&field.tag);
+        for subfield in &mut field.subfields {
+            if subfield.tag.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            let stripped = strip(&subfield.data, keep_period);
+            if stripped.len() != subfield.data.len() {
+                subfield.data = stripped.to_string();
+            }
+        }
+    }
+}
+
+/// Whether the final period of the subfields of a `tag` field belongs to the data.
+pub(crate) fn keeps_period(tag: &str) -> bool {
+    ABBREVIATED_TAGS.contains(&tag)
+}
+
+/// Removes the final ` /`, ` :`, ` ;`, ` =`, `,` and `.` of the data. This is also how the
+/// conversions to other formats remove the punctuation, so that they agree with the
+/// `strip_punctuation` option.
+pub(crate) fn strip(data: &str, keep_period: bool) -> &str {
+    let text = data.trim_end_matches([' ', '/', ':', ';', '=', ',']);
+    match text.strip_suffix('.') {
+        Some(without_period) if !keep_period && !is_abbreviated(without_period) => {
+            without_period.trim_end()
+        }
+        _ => text,
+    }
+}
+
+/// Whether the text before a final period ends with an initial, an abbreviation or an
+/// ellipsis, whose period must be kept.
+fn is_abbreviated(text: &str) -> bool {
+    let last_word = text.rsplit([' ', ',', '(', '-']).next().unwrap_or_default();
+    let mut chars = last_word.chars();
+    let is_initial = matches!((chars.next(), chars.next()), (Some(c), None) if c.is_alphabetic());
+    text.ends_with('.') || is_initial || ABBREVIATIONS.contains(&last_word)
+}
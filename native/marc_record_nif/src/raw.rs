@@ -0,0 +1,315 @@
+/// Byte level access to the ISO 2709 structure of MARC records.
+///
+/// The `marc-record` crate trusts the leader and only exposes decoded records. This module
+/// splits a binary into records and reads the leader and directory
+/// without decoding any field, which is what structural checks need.
+///
+/// It is also the entry point used to decode records one by one: `marc-record` only accepts
+/// the leader values of MARC 21 bibliographic records, so the other values (authority records,
+/// UNIMARC leaders...) are masked before the record is handed to the crate, and are decoded
+/// from the raw leader afterwards.
+use std::borrow::Cow;
+
+use marc_record::{parse_records, Error, Record};
+
+use crate::options::Flavor;
+
+pub const RECORD_TERMINATOR: u8 = 0x1D;
+pub const FIELD_TERMINATOR: u8 = 0x1E;
+pub const SUBFIELD_DELIMITER: u8 = 0x1F;
+pub const LEADER_LENGTH: usize = 24;
+pub const DIRECTORY_ENTRY_LENGTH: usize = 12;
+
+/// The bytes of a single record, terminator included, and their position in the input.
+pub struct RawRecord<'a> {
+    pub offset: usize,
+    pub bytes: &'a [u8],
+}
+
+/// Splits the input into records. A record ends on the record length of its leader (00-04)
+/// when that length ends on a record terminator, or else on the end of its last field according
+/// to the directory, which is what `marc-record` relies on: a record terminator inside field
+/// data does not split the record. The records whose length cannot be found either way end on
+/// the next terminator, and trailing bytes without a terminator are returned as a last,
+/// incomplete, record.
+pub fn split_records(data: &[u8]) -> Vec<RawRecord<'_>> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let record = record_at(data, offset);
+        offset += record.bytes.len();
+        records.push(record);
+    }
+    records
+}
+
+/// The first record of the input, split like `split_records`, without going through the
+/// others.
+pub fn first_record(data: &[u8]) -> Option<RawRecord<'_>> {
+    (!data.is_empty()).then(|| record_at(data, 0))
+}
+
+/// The record starting at `offset`, which must be within the input.
+fn record_at(data: &[u8], offset: usize) -> RawRecord<'_> {
+    let rest = RawRecord {
+        offset,
+        bytes: &data[offset..],
+    };
+    let ends_record = |length: &usize| {
+        *length >= LEADER_LENGTH && rest.bytes.get(length - 1) == Some(&RECORD_TERMINATOR)
+    };
+    let length = rest
+        .leader_number(0, 5)
+        .filter(ends_record)
+        .or_else(|| rest.directory_record_length().filter(ends_record))
+        .unwrap_or_else(|| {
+            rest.bytes
+                .iter()
+                .position(|&byte| byte == RECORD_TERMINATOR)
+                .map_or(rest.bytes.len(), |position| position + 1)
+        });
+    RawRecord {
+        offset,
+        bytes: &rest.bytes[..length],
+    }
+}
+
+/// The byte ranges of consecutive groups of `chunk_size` records, split like `split_records`:
+/// each chunk can be decoded on its own.
+pub fn chunk_ranges(data: &[u8], chunk_size: usize) -> Vec<std::ops::Range<usize>> {
+    split_records(data)
+        .chunks(chunk_size.max(1))
+        .map(|chunk| {
+            let last = &chunk[chunk.len() - 1];
+            chunk[0].offset..last.offset + last.bytes.len()
+        })
+        .collect()
+}
+
+pub struct DirectoryEntry {
+    pub tag: String,
+    pub field_length: usize,
+    pub starting_pos: usize,
+}
+
+impl<'a> RawRecord<'a> {
+    /// Decodes the record with `marc-record`. Parsing errors carry the offset of the failure
+    /// in the whole input rather than in the record.
+    pub fn parse(&self, flavor: Flavor) -> Result<Record, Error> {
+        let shift_offset = |error| match error {
+            Error::ParseFailed { reason, offset } => Error::ParseFailed {
+                reason,
+                offset: self.offset + offset,
+            },
+        };
+        if flavor == Flavor::Unimarc && std::str::from_utf8(self.bytes).is_err() {
+            return Err(Error::ParseFailed {
+                reason: format!(
+                    "UNIMARC record is not UTF-8, its character sets (100 $a/26-29 `{}`) are not supported",
+                    self.character_sets().unwrap_or_default()
+                ),
+                offset: self.offset,
+            });
+        }
+        self.check_fields().map_err(|reason| Error::ParseFailed {
+            reason,
+            offset: self.offset,
+        })?;
+        let mut records = parse_records(&mask_leader(self.bytes, flavor)).map_err(shift_offset)?;
+        records.pop().ok_or_else(|| Error::ParseFailed {
+            reason: "empty record".to_string(),
+            offset: self.offset,
+        })
+    }
+
+    /// Checks that every field of the directory lies within the data of the record, which
+    /// starts after the directory. `marc-record` slices the data without checking and panics
+    /// on a field running past its end, or on an empty control field. A directory which cannot
+    /// be read is left to `marc-record`, which rejects it.
+    fn check_fields(&self) -> Result<(), String> {
+        let (Some(directory), Ok(entries)) = (self.directory_bytes(), self.directory()) else {
+            return Ok(());
+        };
+        let data_length = self
+            .bytes
+            .len()
+            .saturating_sub(LEADER_LENGTH + directory.len() + 1);
+        for entry in entries {
+            if entry.starting_pos + entry.field_length > data_length {
+                return Err(format!(
+                    "field {} ({} bytes at {}) runs past the {} bytes of data",
+                    entry.tag, entry.field_length, entry.starting_pos, data_length
+                ));
+            }
+            if entry.tag.starts_with("00") && entry.field_length == 0 {
+                return Err(format!("control field {} is empty", entry.tag));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn leader(&self) -> Option<&'a [u8]> {
+        self.bytes.get(..LEADER_LENGTH)
+    }
+
+    /// Reads a number stored as ASCII digits in the leader, e.g. the record length.
+    pub fn leader_number(&self, start: usize, length: usize) -> Option<usize> {
+        let digits = self.leader()?.get(start..start + length)?;
+        std::str::from_utf8(digits).ok()?.parse().ok()
+    }
+
+    /// The length of the record up to the end of its last field, terminator included, according
+    /// to the directory.
+    fn directory_record_length(&self) -> Option<usize> {
+        let directory_length = self.directory_bytes()?.len();
+        let data_length = self
+            .directory()
+            .ok()?
+            .iter()
+            .map(|entry| entry.starting_pos + entry.field_length)
+            .max()?;
+        Some(LEADER_LENGTH + directory_length + 1 + data_length + 1)
+    }
+
+    /// The directory bytes, between the leader and the first field terminator.
+    pub fn directory_bytes(&self) -> Option<&'a [u8]> {
+        let rest = self.bytes.get(LEADER_LENGTH..)?;
+        let end = rest.iter().position(|&byte| byte == FIELD_TERMINATOR)?;
+        Some(&rest[..end])
+    }
+
+    /// The position in the input of the data of each subfield, by field in the order of the
+    /// directory. Control fields have no subfields. `None` when the directory cannot be read.
+    pub fn subfield_ranges(&self) -> Option<Vec<Vec<std::ops::Range<usize>>>> {
+        let base_address = self.leader_number(12, 5)?;
+        let entries = self.directory().ok()?;
+        let ranges = entries
+            .iter()
+            .map(|entry| {
+                let start = base_address + entry.starting_pos;
+                let Some(field) = self.bytes.get(start..start + entry.field_length) else {
+                    return Vec::new();
+                };
+                let mut parts = field.split(|&byte| byte == SUBFIELD_DELIMITER);
+                // The indicators come before the first delimiter.
+                let mut offset = self.offset + start + parts.next().map_or(0, <[u8]>::len);
+                let mut ranges = Vec::new();
+                for part in parts {
+                    let data = part.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(part);
+                    // The delimiter and the subfield code come before the data.
+                    let data_start = offset + 2;
+                    ranges.push(data_start..data_start + data.len().saturating_sub(1));
+                    offset += part.len() + 1;
+                }
+                ranges
+            })
+            .collect();
+        Some(ranges)
+    }
+
+    /// The bytes of the first `code` subfield of the first `tag` field, found through the
+    /// directory.
+    pub fn subfield_bytes(&self, tag: &str, code: u8) -> Option<&'a [u8]> {
+        let base_address = self.leader_number(12, 5)?;
+        let entry = self
+            .directory()
+            .ok()?
+            .into_iter()
+            .find(|entry| entry.tag == tag)?;
+        let start = base_address + entry.starting_pos;
+        let field = self.bytes.get(start..start + entry.field_length)?;
+        field
+            .split(|&byte| byte == SUBFIELD_DELIMITER)
+            .skip(1)
+            .find(|part| part.first() == Some(&code))
+            .map(|part| {
+                let data = &part[1..];
+                data.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(data)
+            })
+    }
+
+    /// The character sets of a UNIMARC record, 100 `$a`/26-29, e.g. `0103` for ISO 646 and
+    /// ISO 5426 or `50  ` for Unicode.
+    fn character_sets(&self) -> Option<String> {
+        let data = self.subfield_bytes("100", b'a')?;
+        let sets = data.get(26..30)?;
+        Some(String::from_utf8_lossy(sets).into_owned())
+    }
+
+    pub fn directory(&self) -> Result<Vec<DirectoryEntry>, String> {
+        let directory = self
+            .directory_bytes()
+            .ok_or_else(|| "directory is not terminated".to_string())?;
+        if directory.len() % DIRECTORY_ENTRY_LENGTH != 0 {
+            return Err(format!(
+                "directory length {} is not a multiple of {}",
+                directory.len(),
+                DIRECTORY_ENTRY_LENGTH
+            ));
+        }
+        directory
+            .chunks(DIRECTORY_ENTRY_LENGTH)
+            .map(|entry| {
+                let text = std::str::from_utf8(entry)
+                    .ok()
+                    .filter(|text| text.is_ascii())
+                    .ok_or_else(|| "directory entry is not ASCII".to_string())?;
+                let number = |range: std::ops::Range<usize>| {
+                    text[range]
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid directory entry `{}`", text))
+                };
+                Ok(DirectoryEntry {
+                    tag: text[0..3].to_string(),
+                    field_length: number(3..7)?,
+                    starting_pos: number(7..12)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Leader values defined by the authority format but rejected by `marc-record`, which only
+/// knows the bibliographic ones, along with the value decoded in their place.
+const AUTHORITY_VALUES: &[(usize, &[u8], u8)] =
+    &[(5, b"sx", b'd'), (6, b"z", b'a'), (17, b"no", b'u')];
+
+/// The same for the holdings format, whose record types are `u`, `v`, `x` and `y`.
+const HOLDINGS_VALUES: &[(usize, &[u8], u8)] = &[(6, b"uvxy", b'a'), (17, b"m", b'u')];
+
+/// The same for UNIMARC, whose leader/08 is the hierarchical level.
+const UNIMARC_VALUES: &[(usize, &[u8], u8)] =
+    &[(5, b"o", b'n'), (6, b"bl", b'a'), (8, b"012", b' ')];
+
+/// Replaces the leader values that the format of the record defines but that `marc-record`
+/// would reject, e.g. the record type `z` of authority records, so that the fields can still be
+/// decoded. The replaced values are decoded from the raw leader afterwards. Any other value is
+/// kept, so that `marc-record` rejects the record as it would reject an invalid bibliographic
+/// one.
+///
+/// UNIMARC leaders have no character coding position, the character sets are given in field
+/// 100, and end with an undefined position where MARC 21 expects `0`. Only the records which
+/// are valid UTF-8 reach this point, they are decoded as such.
+fn mask_leader(bytes: &[u8], flavor: Flavor) -> Cow<'_, [u8]> {
+    if bytes.len() < LEADER_LENGTH {
+        return Cow::Borrowed(bytes);
+    }
+    let (values, fixed) = match flavor {
+        Flavor::Marc21 => match bytes[6] {
+            b'z' => (AUTHORITY_VALUES, Vec::new()),
+            b'u' | b'v' | b'x' | b'y' => (HOLDINGS_VALUES, Vec::new()),
+            _ => return Cow::Borrowed(bytes),
+        },
+        Flavor::Unimarc => (UNIMARC_VALUES, vec![(9, b'a'), (23, b'0')]),
+    };
+    let mut masked = bytes.to_vec();
+    for &(position, defined, neutral) in values {
+        if defined.contains(&bytes[position]) {
+            masked[position] = neutral;
+        }
+    }
+    for (position, value) in fixed {
+        masked[position] = value;
+    }
+    Cow::Owned(masked)
+}
@@ -0,0 +1,195 @@
+/// Conversion of MARC 21 bibliographic records to schema.org JSON-LD, the structured data
+/// embedded in catalog pages for search engines. Books are described as `Book`, serials as
+/// `Periodical` and other materials with the closest `CreativeWork` type.
+///
+/// Info about the vocabulary can be found here: https://schema.org/Book
+use crate::crosswalk::{self, subfields_text, Json};
+use crate::leader::{BibliographicalLevelWrapper, RecordTypeWrapper};
+use crate::{DataFieldWrapper, RecordWrapper};
+
+/// The JSON-LD document of a record.
+pub(crate) fn to_schema_org(record: &RecordWrapper) -> String {
+    let schema_type = schema_type(record);
+    let field = |tag: &str| record.data_fields().find(|field| field.tag == tag);
+    let authors = record
+        .data_fields()
+        .filter(|field| ["100", "110", "111", "700", "710", "711"].contains(&field.tag.as_str()))
+        .filter_map(|field| {
+            let (agent_type, codes) = match field.tag.as_str() {
+                "100" | "700" => ("Person", "abcdq"),
+                _ => ("Organization", "abcdn"),
+            };
+            let name = subfields_text(field, codes)?;
+            Some(Json::object(vec![
+                ("@type", Some(Json::string(agent_type))),
+                ("name", Some(Json::string(name))),
+            ]))
+        })
+        .collect::<Vec<_>>();
+    let isbns = record
+        .data_fields()
+        .filter(|field| field.tag == "020")
+        .filter_map(|field| field.subfield("a"))
+        .filter_map(|isbn| isbn.split_whitespace().next())
+        .map(Json::string)
+        .collect::<Vec<_>>();
+    let publisher = crosswalk::publication_field(record)
+        .and_then(|field| subfields_text(field, "b"))
+        .map(|name| {
+            Json::object(vec![
+                ("@type", Some(Json::string("Organization"))),
+                ("name", Some(Json::string(name))),
+            ])
+        });
+    let languages = crosswalk::languages(record)
+        .iter()
+        .map(|code| Json::string(language_tag(code)))
+        .collect::<Vec<_>>();
+    let in_language = match languages.len() {
+        1 => languages.into_iter().next(),
+        _ => Some(Json::Array(languages)),
+    };
+    let is_book = matches!(schema_type, "Book" | "Audiobook");
+    let book_format = if !is_book {
+        None
+    } else if schema_type == "Audiobook" {
+        Some("https://schema.org/AudiobookFormat")
+    } else if crosswalk::is_electronic(record) {
+        Some("https://schema.org/EBook")
+    } else {
+        None
+    };
+    let urls = record
+        .data_fields()
+        .filter(|field| field.tag == "856")
+        .filter_map(|field| field.subfield("u"))
+        .map(|url| Json::string(url.trim()))
+        .collect::<Vec<_>>();
+
+    let document = Json::object(vec![
+        ("@context", Some(Json::string("https://schema.org"))),
+        ("@type", Some(Json::string(schema_type))),
+        ("name", field("245").and_then(title_text).map(Json::string)),
+        ("author", Some(Json::Array(authors))),
+        ("isbn", is_book.then_some(Json::Array(isbns))),
+        (
+            "issn",
+            field("022")
+                .and_then(|issn| subfields_text(issn, "a"))
+                .map(Json::string),
+        ),
+        (
+            "datePublished",
+            crosswalk::publication_year(record).map(Json::string),
+        ),
+        ("inLanguage", in_language),
+        ("publisher", publisher),
+        (
+            "bookEdition",
+            field("250")
+                .filter(|_| is_book)
+                .and_then(|edition| subfields_text(edition, "a"))
+                .map(Json::string),
+        ),
+        ("bookFormat", book_format.map(Json::string)),
+        (
+            "numberOfPages",
+            field("300")
+                .filter(|_| is_book)
+                .and_then(|extent| number_of_pages(extent.subfield("a")?))
+                .map(Json::Number),
+        ),
+        (
+            "description",
+            field("520")
+                .and_then(|summary| subfields_text(summary, "a"))
+                .map(Json::string),
+        ),
+        ("url", Some(Json::Array(urls))),
+    ]);
+    document.to_pretty_string()
+}
+
+/// The type of the resource from leader/06 and /07.
+fn schema_type(record: &RecordWrapper) -> &'static str {
+    let level = record.leader.bibliographic_level();
+    if matches!(
+        level,
+        BibliographicalLevelWrapper::Serial | BibliographicalLevelWrapper::SerialComponentPart
+    ) {
+        return "Periodical";
+    }
+    match record.leader.record_type() {
+        RecordTypeWrapper::LanguageMaterial | RecordTypeWrapper::ManuscriptLanguageMaterial => {
+            match level {
+                BibliographicalLevelWrapper::Monograph
+                | BibliographicalLevelWrapper::Collection
+                | BibliographicalLevelWrapper::Subunit => "Book",
+                _ => "CreativeWork",
+            }
+        }
+        RecordTypeWrapper::CartographicMaterial
+        | RecordTypeWrapper::ManuscriptCartographicMaterial => "Map",
+        RecordTypeWrapper::NotatedMusic | RecordTypeWrapper::ManuscriptNotatedMusic => {
+            "MusicComposition"
+        }
+        RecordTypeWrapper::NonmusicalSoundRecording => "Audiobook",
+        RecordTypeWrapper::MusicalSoundRecording => "MusicRecording",
+        RecordTypeWrapper::ProjectedMedium => "Movie",
+        RecordTypeWrapper::TwoDimensionalNonprojectableGraphic => "VisualArtwork",
+        RecordTypeWrapper::ComputerFile => "SoftwareApplication",
+        _ => "CreativeWork",
+    }
+}
+
+/// The title proper and the other title information, as in `Title : subtitle`.
+fn title_text(field: &DataFieldWrapper) -> Option<String> {
+    let title = subfields_text(field, "a")?;
+    Some(match subfields_text(field, "b") {
+        Some(subtitle) => format!("{} : {}", title, subtitle),
+        None => title,
+    })
+}
+
+/// The page count of an extent such as `xii, 159 p.` or `1 ressource en ligne (159 p.)`.
+fn number_of_pages(extent: &str) -> Option<i64> {
+    let words = extent
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ',')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    words.windows(2).find_map(|pair| {
+        let is_page = matches!(pair[1].trim_end_matches(['.', ')']), "p" | "pages" | "pp");
+        pair[0].parse().ok().filter(|_| is_page)
+    })
+}
+
+/// The BCP 47 tag of a MARC language code, for the languages which have a two letter code.
+/// Other codes are kept as is, which is a valid tag for the ISO 639-2 languages.
+fn language_tag(code: &str) -> &str {
+    match code {
+        "ara" => "ar",
+        "chi" => "zh",
+        "cze" => "cs",
+        "dan" => "da",
+        "dut" => "nl",
+        "eng" => "en",
+        "fin" => "fi",
+        "fre" => "fr",
+        "ger" => "de",
+        "gre" => "el",
+        "heb" => "he",
+        "hun" => "hu",
+        "ita" => "it",
+        "jpn" => "ja",
+        "kor" => "ko",
+        "lat" => "la",
+        "nor" => "no",
+        "pol" => "pl",
+        "por" => "pt",
+        "rus" => "ru",
+        "spa" => "es",
+        "swe" => "sv",
+        "tur" => "tr",
+        _ => code,
+    }
+}
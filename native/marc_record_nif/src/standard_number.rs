@@ -0,0 +1,213 @@
+/// Parsing and validation of the ISBNs (020) and ISSNs (022) of a record. The values are often
+/// recorded with hyphens and followed by a qualifier, e.g. `978-2-7644-1234-5 (PDF)`: the number
+/// is separated from its qualifiers, its check digit is verified and ISBN-10 are converted to
+/// ISBN-13.
+///
+/// Numbers in `$a` are the ones of the item; the other subfields hold numbers known to be
+/// wrong: cancelled or invalid ISBNs (020 `$z`), incorrect (022 `$y`) or cancelled (022 `$z`)
+/// ISSNs.
+///
+/// Info about the check digits can be found here:
+/// - https://www.isbn-international.org/content/isbn-users-manual
+/// - https://www.issn.org/understanding-the-issn/what-is-an-issn/
+use rustler::{Encoder, Env, Term};
+
+use crate::punctuation;
+use crate::{DataFieldWrapper, RecordWrapper};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    Isbn,
+    Issn,
+}
+
+pub struct StandardNumber {
+    pub kind: Kind,
+    /// The code of the subfield the number comes from.
+    pub subfield: String,
+    pub raw: String,
+    /// The ISBN-13 or the ISSN with its hyphen (`1234-5679`) when the number is valid, or else
+    /// the number without hyphens.
+    pub number: String,
+    /// The ISBN-10 of a valid ISBN starting with `978`.
+    pub isbn10: Option<String>,
+    pub qualifiers: Vec<String>,
+    /// Whether the number has the right length and check digit.
+    pub valid: bool,
+    /// Whether the number is recorded as cancelled or invalid (any subfield but `$a`).
+    pub cancelled: bool,
+}
+
+impl StandardNumber {
+    /// Parses a value such as `2-7644-1234-X (pbk.)`, adding the qualifiers of the field.
+    fn from_subfield(kind: Kind, subfield: &str, value: &str, mut qualifiers: Vec<String>) -> Self {
+        let value = value.trim();
+        let end = value
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, 'X' | 'x' | '-')))
+            .unwrap_or(value.len());
+        let digits = value[..end]
+            .chars()
+            .filter(|c| *c != '-')
+            .map(|c| c.to_ascii_uppercase())
+            .collect::<String>();
+        qualifiers.splice(0..0, parse_qualifiers(&value[end..]));
+
+        let (valid, number, isbn10) = match kind {
+            Kind::Isbn if is_valid_isbn10(&digits) => {
+                (true, isbn10_to_isbn13(&digits), Some(digits.clone()))
+            }
+            Kind::Isbn if is_valid_isbn13(&digits) => {
+                (true, digits.clone(), isbn13_to_isbn10(&digits))
+            }
+            Kind::Issn if is_valid_issn(&digits) => {
+                (true, format!("{}-{}", &digits[..4], &digits[4..]), None)
+            }
+            _ => (false, digits, None),
+        };
+        StandardNumber {
+            kind,
+            subfield: subfield.to_string(),
+            raw: value.to_string(),
+            number,
+            isbn10,
+            qualifiers,
+            valid,
+            cancelled: subfield != "a",
+        }
+    }
+
+    /// Whether this is a valid number of the item itself, the one to use for lookups.
+    pub fn is_usable(&self) -> bool {
+        self.valid && !self.cancelled
+    }
+}
+
+/// The ISBNs and ISSNs of the record, in the order of the fields.
+pub(crate) fn standard_numbers(record: &RecordWrapper) -> Vec<StandardNumber> {
+    record
+        .data_fields()
+        .flat_map(|field| match field.tag.as_str() {
+            "020" => field_numbers(field, Kind::Isbn, "az"),
+            "022" => field_numbers(field, Kind::Issn, "ayz"),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+fn field_numbers(field: &DataFieldWrapper, kind: Kind, codes: &str) -> Vec<StandardNumber> {
+    // Qualifiers are in `$q` since 2013, they apply to every number of the field.
+    let qualifiers = field
+        .subfields
+        .iter()
+        .filter(|subfield| subfield.tag == "q")
+        .flat_map(|subfield| parse_qualifiers(&subfield.data))
+        .collect::<Vec<_>>();
+    field
+        .subfields
+        .iter()
+        .filter(|subfield| codes.contains(subfield.tag.as_str()))
+        .filter(|subfield| !subfield.data.trim().is_empty())
+        .map(|subfield| {
+            StandardNumber::from_subfield(kind, &subfield.tag, &subfield.data, qualifiers.clone())
+        })
+        .collect()
+}
+
+/// Splits `(v. 1 ; pbk.) :` into `v. 1` and `pbk.`.
+fn parse_qualifiers(text: &str) -> Vec<String> {
+    text.split([';', '(', ')'])
+        .map(|qualifier| punctuation::strip(qualifier.trim_matches([' ', ':']), false))
+        .filter(|qualifier| !qualifier.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn digit_values(number: &str) -> Option<Vec<u32>> {
+    let len = number.len();
+    number
+        .chars()
+        .enumerate()
+        .map(|(position, c)| match c {
+            'X' if position == len - 1 => Some(10),
+            _ => c.to_digit(10),
+        })
+        .collect()
+}
+
+fn is_valid_isbn10(number: &str) -> bool {
+    number.len() == 10
+        && digit_values(number).is_some_and(|digits| {
+            let sum: u32 = digits.iter().zip((1..=10).rev()).map(|(d, w)| d * w).sum();
+            sum.is_multiple_of(11)
+        })
+}
+
+fn is_valid_isbn13(number: &str) -> bool {
+    (number.starts_with("978") || number.starts_with("979"))
+        && number.len() == 13
+        && number.chars().all(|c| c.is_ascii_digit())
+        && isbn13_check_digit(&number[..12]) == number[12..]
+}
+
+fn is_valid_issn(number: &str) -> bool {
+    number.len() == 8
+        && digit_values(number).is_some_and(|digits| {
+            let sum: u32 = digits.iter().zip((1..=8).rev()).map(|(d, w)| d * w).sum();
+            sum.is_multiple_of(11)
+        })
+}
+
+fn isbn13_check_digit(first_twelve: &str) -> String {
+    let sum: u32 = first_twelve
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .zip([1, 3].into_iter().cycle())
+        .map(|(d, w)| d * w)
+        .sum();
+    ((10 - sum % 10) % 10).to_string()
+}
+
+fn isbn10_to_isbn13(isbn10: &str) -> String {
+    let first_twelve = format!("978{}", &isbn10[..9]);
+    let check_digit = isbn13_check_digit(&first_twelve);
+    first_twelve + &check_digit
+}
+
+/// Only the ISBN-13 of the `978` prefix have an ISBN-10.
+fn isbn13_to_isbn10(isbn13: &str) -> Option<String> {
+    let body = isbn13.strip_prefix("978")?.get(..9)?;
+    let sum: u32 = body
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .zip((2..=10).rev())
+        .map(|(d, w)| d * w)
+        .sum();
+    let check_digit = match (11 - sum % 11) % 11 {
+        10 => 'X',
+        digit => char::from_digit(digit, 10)?,
+    };
+    Some(format!("{}{}", body, check_digit))
+}
+
+impl Encoder for StandardNumber {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let kind = match self.kind {
+            Kind::Isbn => "isbn",
+            Kind::Issn => "issn",
+        };
+        Term::map_from_pairs(
+            env,
+            &[
+                ("type", kind.encode(env)),
+                ("subfield", self.subfield.encode(env)),
+                ("raw", self.raw.encode(env)),
+                ("number", self.number.encode(env)),
+                ("isbn10", self.isbn10.encode(env)),
+                ("qualifiers", self.qualifiers.encode(env)),
+                ("valid", self.valid.encode(env)),
+                ("cancelled", self.cancelled.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
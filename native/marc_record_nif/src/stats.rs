@@ -0,0 +1,114 @@
+/// Statistics of a file, computed in one pass over the records: the distribution of the main
+/// leader values, the frequency of the tags and of the subfield codes of each tag, and the
+/// records which cannot be decoded. The records are decoded but neither wrapped nor encoded.
+///
+/// A record with a leader value its format does not define, or with a directory pointing past
+/// its data, cannot be decoded, so it is counted as invalid rather than in the leader
+/// distributions.
+use std::collections::HashMap;
+
+use marc_record::Field;
+use rustler::{Encoder, Env, Term};
+
+use crate::leader::LeaderWrapper;
+use crate::options::ParseOptions;
+use crate::raw;
+
+#[derive(Default)]
+pub struct Stats {
+    pub record_count: usize,
+    pub invalid_count: usize,
+    /// The number of bytes of all the records, terminators included.
+    pub total_length: usize,
+    pub record_types: HashMap<&'static str, usize>,
+    pub bibliographic_levels: HashMap<&'static str, usize>,
+    pub encoding_levels: HashMap<&'static str, usize>,
+    pub coding_schemes: HashMap<&'static str, usize>,
+    /// The number of fields of each tag.
+    pub tags: HashMap<String, usize>,
+    /// The number of subfields of each code, by tag.
+    pub subfields: HashMap<String, HashMap<String, usize>>,
+}
+
+impl Stats {
+    pub fn average_length(&self) -> f64 {
+        if self.record_count == 0 {
+            return 0.0;
+        }
+        self.total_length as f64 / self.record_count as f64
+    }
+}
+
+pub(crate) fn stats(data: &[u8], options: &ParseOptions) -> Stats {
+    let mut stats = Stats::default();
+    for raw in raw::split_records(data) {
+        stats.record_count += 1;
+        stats.total_length += raw.bytes.len();
+        let Ok(record) = raw.parse(options.flavor) else {
+            stats.invalid_count += 1;
+            continue;
+        };
+
+        let leader = LeaderWrapper::new(
+            record.leader,
+            raw.leader().unwrap_or_default(),
+            options.flavor,
+        );
+        *stats
+            .record_types
+            .entry(leader.record_type().name())
+            .or_default() += 1;
+        *stats
+            .bibliographic_levels
+            .entry(leader.bibliographic_level().name())
+            .or_default() += 1;
+        *stats
+            .encoding_levels
+            .entry(leader.encoding_level().name())
+            .or_default() += 1;
+        *stats
+            .coding_schemes
+            .entry(leader.coding_scheme().name())
+            .or_default() += 1;
+
+        for field in &record.fields {
+            match field {
+                Field::Control(control) => {
+                    *stats.tags.entry(control.tag.to_string()).or_default() += 1;
+                }
+                Field::Data(data) => {
+                    let tag = data.tag.to_string();
+                    let codes = stats.subfields.entry(tag.clone()).or_default();
+                    for subfield in &data.subfields {
+                        *codes.entry(subfield.tag.to_string()).or_default() += 1;
+                    }
+                    *stats.tags.entry(tag).or_default() += 1;
+                }
+            }
+        }
+    }
+    stats
+}
+
+impl Encoder for Stats {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("record_count", self.record_count.encode(env)),
+                ("invalid_count", self.invalid_count.encode(env)),
+                ("average_length", self.average_length().encode(env)),
+                ("record_types", self.record_types.encode(env)),
+                (
+                    "bibliographic_levels",
+                    self.bibliographic_levels.encode(env),
+                ),
+                ("encoding_levels", self.encoding_levels.encode(env)),
+                ("coding_schemes", self.coding_schemes.encode(env)),
+                ("tags", self.tags.encode(env)),
+                ("subfields", self.subfields.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
@@ -0,0 +1,156 @@
+/// Structural checks of the ISO 2709 envelope of each record.
+///
+/// The `marc-record` parser trusts the leader: it neither verifies the record length nor the
+/// base address of data, and it rejects the whole file as soon as one record is broken. Here
+/// every record is checked on its own, straight from the bytes, and the problems are reported
+/// as warnings next to the parsed record.
+use rustler::{Encoder, Env, Term};
+
+use crate::options::ParseOptions;
+use crate::raw::{RawRecord, FIELD_TERMINATOR, LEADER_LENGTH, RECORD_TERMINATOR};
+use crate::validator::{Issue, Severity};
+use crate::RecordWrapper;
+
+pub struct CheckedRecord {
+    record: Option<RecordWrapper>,
+    warnings: Vec<Issue>,
+}
+
+impl CheckedRecord {
+    pub(crate) fn new(raw: &RawRecord) -> Self {
+        let mut warnings = check_structure(raw);
+        let options = ParseOptions::default();
+        let record = match raw.parse(options.flavor) {
+            Ok(record) => Some(RecordWrapper::new(
+                record,
+                raw.leader().unwrap_or_default(),
+                &options,
+            )),
+            Err(error) => {
+                warnings.push(Issue::record_issue(
+                    Severity::Error,
+                    format!("Error in crate marc-record: {}", error),
+                ));
+                None
+            }
+        };
+        CheckedRecord { record, warnings }
+    }
+}
+
+impl Encoder for CheckedRecord {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("record", self.record.encode(env)),
+                ("warnings", self.warnings.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+/// Compares what the leader and the directory announce with the actual bytes of the record.
+pub(crate) fn check_structure(raw: &RawRecord) -> Vec<Issue> {
+    let mut warnings = Vec::new();
+    let Some(leader) = raw.leader() else {
+        warnings.push(warning(format!(
+            "record is {} bytes long, shorter than the leader",
+            raw.bytes.len()
+        )));
+        return warnings;
+    };
+
+    match raw.leader_number(0, 5) {
+        Some(length) if length != raw.bytes.len() => warnings.push(warning(format!(
+            "leader record length {} does not match actual length {}",
+            length,
+            raw.bytes.len()
+        ))),
+        Some(_) => {}
+        None => warnings.push(warning("leader record length is not numeric".to_string())),
+    }
+    for (position, name) in [(10, "indicator count"), (11, "subfield code count")] {
+        if leader[position] != b'2' {
+            warnings.push(warning(format!(
+                "{} is `{}`, expected 2",
+                name, leader[position] as char
+            )));
+        }
+    }
+    if raw.bytes.last() != Some(&RECORD_TERMINATOR) {
+        warnings.push(warning("record is not terminated".to_string()));
+    }
+
+    let directory = match raw.directory() {
+        Ok(directory) => directory,
+        Err(message) => {
+            warnings.push(warning(message));
+            return warnings;
+        }
+    };
+    let directory_length = raw.directory_bytes().map_or(0, <[u8]>::len);
+    let base_address = LEADER_LENGTH + directory_length + 1;
+    match raw.leader_number(12, 5) {
+        Some(address) if address != base_address => warnings.push(warning(format!(
+            "leader data base address {} does not match end of directory {}",
+            address, base_address
+        ))),
+        Some(_) => {}
+        None => warnings.push(warning(
+            "leader data base address is not numeric".to_string(),
+        )),
+    }
+
+    let data = raw.bytes.get(base_address..).unwrap_or_default();
+    let data = data.strip_suffix(&[RECORD_TERMINATOR]).unwrap_or(data);
+    let mut entries = directory.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.starting_pos);
+    let mut expected_pos = 0;
+    for entry in entries {
+        if entry.starting_pos < expected_pos {
+            warnings.push(Issue::warning(
+                &entry.tag,
+                None,
+                format!("field {} overlaps the previous field", entry.tag),
+            ));
+        } else if entry.starting_pos > expected_pos {
+            warnings.push(Issue::warning(
+                &entry.tag,
+                None,
+                format!(
+                    "gap of {} bytes before field {}",
+                    entry.starting_pos - expected_pos,
+                    entry.tag
+                ),
+            ));
+        }
+        let end = entry.starting_pos + entry.field_length;
+        if entry.field_length == 0 || data.get(end - 1) != Some(&FIELD_TERMINATOR) {
+            warnings.push(Issue::warning(
+                &entry.tag,
+                None,
+                format!("field {} does not end with a field terminator", entry.tag),
+            ));
+        }
+        expected_pos = expected_pos.max(end);
+    }
+    if expected_pos < data.len() {
+        warnings.push(warning(format!(
+            "gap of {} bytes after the last field",
+            data.len() - expected_pos
+        )));
+    } else if expected_pos > data.len() {
+        warnings.push(warning(format!(
+            "directory points {} bytes past the end of the record",
+            expected_pos - data.len()
+        )));
+    }
+
+    warnings
+}
+
+fn warning(message: String) -> Issue {
+    Issue::record_issue(Severity::Warning, message)
+}
@@ -0,0 +1,98 @@
+/// The bibliographic data most consumers need from a record, extracted once with the same rules
+/// for everyone: the title proper, the main author, the ISBNs and ISSNs, the publication year,
+/// the languages and the subjects. The ISBNs (as ISBN-13) and ISSNs are the valid ones of `$a`.
+use rustler::{Encoder, Env, Term};
+
+use crate::crosswalk::{self, subfields_text};
+use crate::punctuation;
+use crate::standard_number::{self, Kind};
+use crate::RecordWrapper;
+
+/// Subject access fields; genres (655) and index terms of other kinds are left out.
+const SUBJECT_TAGS: [&str; 8] = ["600", "610", "611", "630", "648", "650", "651", "653"];
+
+pub struct Summary {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub isbns: Vec<String>,
+    pub issns: Vec<String>,
+    pub year: Option<i64>,
+    pub languages: Vec<String>,
+    pub subjects: Vec<String>,
+}
+
+impl Summary {
+    pub(crate) fn new(record: &RecordWrapper) -> Self {
+        let title = record
+            .data_fields()
+            .find(|field| field.tag == "245")
+            .and_then(|field| {
+                // Only the trailing punctuation goes, the one between the title and the
+                // remainder of the title is kept: `Title : subtitle`.
+                let text = field
+                    .subfields
+                    .iter()
+                    .filter(|subfield| subfield.tag == "a" || subfield.tag == "b")
+                    .map(|subfield| subfield.data.trim())
+                    .filter(|data| !data.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(punctuation::strip(&text, false).to_string()).filter(|title| !title.is_empty())
+            });
+        let author = record
+            .data_fields()
+            .find(|field| matches!(field.tag.as_str(), "100" | "110" | "111"))
+            .and_then(|field| subfields_text(field, "abcdnq"));
+        let standard_numbers = standard_number::standard_numbers(record);
+        let numbers = |kind: Kind| {
+            let mut numbers = Vec::new();
+            for number in standard_numbers
+                .iter()
+                .filter(|number| number.kind == kind && number.is_usable())
+            {
+                if !numbers.contains(&number.number) {
+                    numbers.push(number.number.clone());
+                }
+            }
+            numbers
+        };
+        let mut subjects = Vec::new();
+        for subject in record
+            .data_fields()
+            .filter(|field| SUBJECT_TAGS.contains(&field.tag.as_str()))
+            .map(crosswalk::heading_text)
+        {
+            if !subject.is_empty() && !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+
+        Summary {
+            title,
+            author,
+            isbns: numbers(Kind::Isbn),
+            issns: numbers(Kind::Issn),
+            year: crosswalk::publication_year(record).and_then(|year| year.parse().ok()),
+            languages: crosswalk::languages(record),
+            subjects,
+        }
+    }
+}
+
+impl Encoder for Summary {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("title", self.title.encode(env)),
+                ("author", self.author.encode(env)),
+                ("isbns", self.isbns.encode(env)),
+                ("issns", self.issns.encode(env)),
+                ("year", self.year.encode(env)),
+                ("languages", self.languages.encode(env)),
+                ("subjects", self.subjects.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
@@ -0,0 +1,505 @@
+/// Support for UNIMARC bibliographic records, read with the `flavor: :unimarc` option.
+///
+/// UNIMARC groups its fields in blocks by function rather than by access point: the title is
+/// in 200, the general processing data, which plays the part of the MARC 21 008 field, is in
+/// 100 and the authors are in the 7XX block. A crosswalk converts the core fields between
+/// UNIMARC and MARC 21; fields without an equivalent are left out of the converted record.
+///
+/// Info about the format can be found here: https://www.ifla.org/g/unimarc-rg/unimarc-bibliographic/
+use rustler::{Encoder, Env, Term};
+
+use crate::fixed_data::{FixedLengthData, Layout};
+use crate::options::Flavor;
+use crate::{ControlFieldWrapper, DataFieldWrapper, FieldWrapper, RecordWrapper, SubfieldWrapper};
+
+pub struct UnimarcRecord {
+    control_number: Option<String>,
+    title: Option<String>,
+    authors: Vec<Author>,
+    general_processing_data: Option<FixedLengthData>,
+}
+
+impl UnimarcRecord {
+    pub(crate) fn new(record: &RecordWrapper) -> Self {
+        let general_processing_data = record
+            .data_fields()
+            .find(|field| field.tag == "100")
+            .and_then(|field| field.subfield("a"))
+            .map(|data| FixedLengthData::new(data, GENERAL_PROCESSING_DATA));
+        UnimarcRecord {
+            control_number: record.control_field("001").map(str::to_string),
+            title: record
+                .data_fields()
+                .find(|field| field.tag == "200")
+                .map(title),
+            authors: record
+                .data_fields()
+                .filter(|field| AUTHOR_TAGS.contains(&field.tag.as_str()))
+                .map(Author::new)
+                .collect(),
+            general_processing_data,
+        }
+    }
+}
+
+impl Encoder for UnimarcRecord {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("control_number", self.control_number.encode(env)),
+                ("title", self.title.encode(env)),
+                ("authors", self.authors.encode(env)),
+                (
+                    "general_processing_data",
+                    self.general_processing_data.encode(env),
+                ),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+/// The title proper (`$a`), followed by the other title information (`$e`) and the first
+/// statement of responsibility (`$f`) with the ISBD punctuation.
+fn title(field: &DataFieldWrapper) -> String {
+    let mut title = field.subfield("a").unwrap_or_default().trim().to_string();
+    if let Some(other) = field.subfield("e") {
+        title = format!("{} : {}", title, other.trim());
+    }
+    if let Some(responsibility) = field.subfield("f") {
+        title = format!("{} / {}", title, responsibility.trim());
+    }
+    title
+}
+
+/// Main (700, 710), alternative (701, 711) and secondary (702, 712) responsibility.
+const AUTHOR_TAGS: [&str; 6] = ["700", "701", "702", "710", "711", "712"];
+
+/// A personal or corporate name of the 7XX block with its relator code (`$4`).
+pub struct Author {
+    tag: String,
+    name: String,
+    relator_code: Option<String>,
+}
+
+impl Author {
+    fn new(field: &DataFieldWrapper) -> Self {
+        let part = |code| field.subfield(code).map(str::trim);
+        let mut name = part("a").unwrap_or_default().to_string();
+        if let Some(forename) = part("b") {
+            name = format!("{}, {}", name, forename);
+        }
+        if let Some(dates) = part("f") {
+            name = format!("{} ({})", name, dates);
+        }
+        Author {
+            tag: field.tag.clone(),
+            name,
+            relator_code: part("4").map(str::to_string),
+        }
+    }
+}
+
+impl Encoder for Author {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        Term::map_from_pairs(
+            env,
+            &[
+                ("tag", self.tag.encode(env)),
+                ("name", self.name.encode(env)),
+                ("relator_code", self.relator_code.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
+
+/// Layout of the general processing data, 100 `$a`.
+const GENERAL_PROCESSING_DATA: Layout = &[
+    ("date_entered_on_file", 0, 8, &[]),
+    (
+        "type_of_publication_date",
+        8,
+        1,
+        &[
+            ('a', "serial_currently_published"),
+            ('b', "serial_ceased_publication"),
+            ('c', "serial_of_unknown_status"),
+            ('d', "monograph_single_date"),
+            ('e', "reproduction"),
+            ('f', "monograph_uncertain_date"),
+            ('g', "monograph_published_over_several_years"),
+            ('h', "monograph_with_publication_and_copyright_dates"),
+            ('i', "monograph_with_production_and_release_dates"),
+            ('j', "document_with_detailed_publication_date"),
+            ('k', "monograph_with_publication_and_printing_dates"),
+            ('u', "unknown"),
+        ],
+    ),
+    ("publication_date_1", 9, 4, &[]),
+    ("publication_date_2", 13, 4, &[]),
+    ("target_audience", 17, 3, &[]),
+    (
+        "government_publication",
+        20,
+        1,
+        &[
+            ('a', "federal_or_national"),
+            ('b', "state_or_province"),
+            ('c', "county_or_department"),
+            ('d', "local"),
+            ('e', "inter_territorial"),
+            ('f', "intergovernmental"),
+            ('g', "government_in_exile"),
+            ('h', "level_undetermined"),
+            ('u', "unknown"),
+            ('y', "not_a_government_publication"),
+            ('z', "other"),
+        ],
+    ),
+    (
+        "modified_record",
+        21,
+        1,
+        &[('0', "not_modified"), ('1', "modified")],
+    ),
+    ("language_of_cataloguing", 22, 3, &[]),
+    (
+        "transliteration",
+        25,
+        1,
+        &[
+            ('a', "iso_transliteration"),
+            ('b', "other_rules"),
+            ('c', "multiple_transliterations"),
+            ('y', "no_transliteration"),
+        ],
+    ),
+    ("character_sets", 26, 4, &[]),
+    ("additional_character_sets", 30, 4, &[]),
+    ("script_of_title", 34, 2, &[]),
+];
+
+/// How a field is converted: the tags and indicators in both formats, and the pairs of
+/// subfield codes, the UNIMARC code first. Empty indicators keep the ones of the source field.
+struct FieldMapping {
+    unimarc: &'static str,
+    marc21: &'static str,
+    unimarc_indicator: &'static str,
+    marc21_indicator: &'static str,
+    subfields: &'static str,
+}
+
+const fn field(
+    unimarc: &'static str,
+    marc21: &'static str,
+    unimarc_indicator: &'static str,
+    marc21_indicator: &'static str,
+    subfields: &'static str,
+) -> FieldMapping {
+    FieldMapping {
+        unimarc,
+        marc21,
+        unimarc_indicator,
+        marc21_indicator,
+        subfields,
+    }
+}
+
+/// When several UNIMARC fields map to the same MARC 21 field, the first one is used to convert
+/// back to UNIMARC.
+const FIELDS: &[FieldMapping] = &[
+    field("010", "020", "  ", "  ", "aa dc zz"),
+    field("011", "022", "  ", "  ", "aa yy zz"),
+    field("101", "041", "", "  ", "aa ch"),
+    field("200", "245", "1 ", "10", "aa eb fc hn ip"),
+    field("205", "250", "  ", "  ", "aa fb"),
+    field("210", "260", "  ", "  ", "aa cb dc"),
+    field("214", "264", "", "", "aa cb dc"),
+    field("215", "300", "  ", "  ", "aa cb dc ee"),
+    field("225", "490", "", "0 ", "aa vv xx"),
+    field("300", "500", "  ", "  ", "aa"),
+    field("330", "520", "  ", "  ", "aa"),
+    field("606", "650", "  ", " 4", "aa jv xx yz zy 22"),
+    field("607", "651", "  ", " 4", "aa jv xx yz zy 22"),
+    field("608", "655", "  ", " 4", "aa jv xx yz zy 22"),
+    field("610", "653", "", "", "aa"),
+    field("675", "080", "  ", "  ", "aa"),
+    field("676", "082", "  ", "04", "aa v2"),
+    field("680", "050", "  ", " 4", "aa bb"),
+    field("700", "100", " 1", "1 ", "fd 44"),
+    field("701", "700", " 1", "1 ", "fd 44"),
+    field("702", "700", " 1", "1 ", "fd 44"),
+    field("710", "110", "02", "2 ", "aa bb 44"),
+    field("711", "710", "02", "2 ", "aa bb 44"),
+    field("712", "710", "02", "2 ", "aa bb 44"),
+    field("856", "856", "", "", "uu zz yy"),
+];
+
+/// Personal names are split into entry element (`$a`) and rest of the name (`$b`) in UNIMARC
+/// but held as a single inverted name in MARC 21 `$a`.
+const PERSONAL_NAMES: [&str; 3] = ["700", "701", "702"];
+
+/// Numeric UNIMARC relator codes and their MARC 21 equivalents.
+const RELATOR_CODES: [(&str, &str); 8] = [
+    ("070", "aut"),
+    ("205", "ctb"),
+    ("340", "edt"),
+    ("440", "ill"),
+    ("600", "pht"),
+    ("650", "pbl"),
+    ("730", "trl"),
+    ("727", "dgs"),
+];
+
+/// Types of publication date (100 `$a`/8) and the MARC 21 008/06 values.
+const DATE_TYPES: [(char, char); 10] = [
+    ('d', 's'),
+    ('a', 'c'),
+    ('b', 'd'),
+    ('c', 'u'),
+    ('e', 'r'),
+    ('f', 'q'),
+    ('g', 'm'),
+    ('h', 't'),
+    ('j', 'e'),
+    ('u', 'n'),
+];
+
+/// Converts a UNIMARC record to MARC 21.
+pub(crate) fn to_marc21(record: RecordWrapper) -> RecordWrapper {
+    let mut fields = control_fields(&record);
+    if let Some(data) = general_processing_data(&record) {
+        fields.push(control_field("008", fixed_length_data(&record, data)));
+    }
+    for source in record.data_fields() {
+        if let Some(mapping) = FIELDS.iter().find(|mapping| mapping.unimarc == source.tag) {
+            let mut field = convert(source, mapping.marc21, mapping.marc21_indicator, |code| {
+                find_code(mapping.subfields, code, false)
+            });
+            if PERSONAL_NAMES.contains(&mapping.unimarc) {
+                field.subfields.insert(0, joined_name(source));
+            }
+            convert_relator_codes(&mut field, false);
+            fields.push(FieldWrapper::Data(field));
+        }
+    }
+    converted(record, Flavor::Marc21, fields)
+}
+
+/// Converts a MARC 21 record to UNIMARC.
+pub(crate) fn to_unimarc(record: RecordWrapper) -> RecordWrapper {
+    let mut fields = control_fields(&record);
+    if let Some(data) = record.control_field("008") {
+        let data = general_processing_from(&record, data);
+        fields.push(FieldWrapper::Data(DataFieldWrapper {
+            tag: "100".to_string(),
+            indicator: "  ".to_string(),
+            subfields: vec![subfield("a", data)],
+            linked: None,
+        }));
+    }
+    for source in record.data_fields() {
+        if let Some(mapping) = FIELDS.iter().find(|mapping| mapping.marc21 == source.tag) {
+            let mut field = convert(source, mapping.unimarc, mapping.unimarc_indicator, |code| {
+                find_code(mapping.subfields, code, true)
+            });
+            if PERSONAL_NAMES.contains(&mapping.unimarc) {
+                field.subfields.splice(0..0, split_name(source));
+            }
+            convert_relator_codes(&mut field, true);
+            fields.push(FieldWrapper::Data(field));
+        }
+    }
+    converted(record, Flavor::Unimarc, fields)
+}
+
+fn converted(
+    record: RecordWrapper,
+    flavor: Flavor,
+    mut fields: Vec<FieldWrapper>,
+) -> RecordWrapper {
+    fields.sort_by(|a, b| field_tag(a).cmp(field_tag(b)));
+    RecordWrapper {
+        leader: record.leader.with_flavor(flavor),
+        fields,
+    }
+}
+
+fn field_tag(field: &FieldWrapper) -> &str {
+    match field {
+        FieldWrapper::Control(control) => &control.tag,
+        FieldWrapper::Data(data) => &data.tag,
+    }
+}
+
+/// The record identifiers and the date of the latest transaction are shared by both formats.
+fn control_fields(record: &RecordWrapper) -> Vec<FieldWrapper> {
+    ["001", "003", "005"]
+        .iter()
+        .filter_map(|tag| Some(control_field(tag, record.control_field(tag)?.to_string())))
+        .collect()
+}
+
+fn control_field(tag: &str, data: String) -> FieldWrapper {
+    FieldWrapper::Control(ControlFieldWrapper {
+        tag: tag.to_string(),
+        data,
+    })
+}
+
+fn subfield(code: &str, data: String) -> SubfieldWrapper {
+    SubfieldWrapper {
+        tag: code.to_string(),
+        data,
+        source: None,
+    }
+}
+
+/// Copies the subfields which have an equivalent under the new tag.
+fn convert(
+    source: &DataFieldWrapper,
+    tag: &str,
+    indicator: &str,
+    code_of: impl Fn(&str) -> Option<&'static str>,
+) -> DataFieldWrapper {
+    let indicator = if indicator.is_empty() {
+        source.indicator.clone()
+    } else {
+        indicator.to_string()
+    };
+    let subfields = source
+        .subfields
+        .iter()
+        .filter_map(|source| Some(subfield(code_of(&source.tag)?, source.data.clone())))
+        .collect();
+    DataFieldWrapper {
+        tag: tag.to_string(),
+        indicator,
+        subfields,
+        linked: None,
+    }
+}
+
+/// Looks a subfield code up in the pairs of a mapping, from MARC 21 to UNIMARC when `reverse`.
+fn find_code(pairs: &'static str, code: &str, reverse: bool) -> Option<&'static str> {
+    pairs.split_whitespace().find_map(|pair| {
+        let (unimarc, marc21) = pair.split_at(1);
+        match reverse {
+            false if unimarc == code => Some(marc21),
+            true if marc21 == code => Some(unimarc),
+            _ => None,
+        }
+    })
+}
+
+fn convert_relator_codes(field: &mut DataFieldWrapper, reverse: bool) {
+    for subfield in field
+        .subfields
+        .iter_mut()
+        .filter(|subfield| subfield.tag == "4")
+    {
+        let code = subfield.data.trim();
+        let converted = RELATOR_CODES
+            .iter()
+            .find_map(|(unimarc, marc21)| match reverse {
+                false if *unimarc == code => Some(*marc21),
+                true if *marc21 == code => Some(*unimarc),
+                _ => None,
+            });
+        if let Some(converted) = converted {
+            subfield.data = converted.to_string();
+        }
+    }
+}
+
+fn joined_name(field: &DataFieldWrapper) -> SubfieldWrapper {
+    let surname = field.subfield("a").unwrap_or_default().trim();
+    let name = match field.subfield("b") {
+        Some(forename) => format!("{}, {}", surname, forename.trim()),
+        None => surname.to_string(),
+    };
+    subfield("a", name)
+}
+
+fn split_name(field: &DataFieldWrapper) -> Vec<SubfieldWrapper> {
+    let name = field
+        .subfield("a")
+        .unwrap_or_default()
+        .trim()
+        .trim_end_matches(',');
+    match name.split_once(", ") {
+        Some((surname, forename)) => vec![
+            subfield("a", surname.to_string()),
+            subfield("b", forename.to_string()),
+        ],
+        None => vec![subfield("a", name.to_string())],
+    }
+}
+
+fn general_processing_data(record: &RecordWrapper) -> Option<&str> {
+    record
+        .data_fields()
+        .find(|field| field.tag == "100")?
+        .subfield("a")
+}
+
+fn subfield_language(record: &RecordWrapper, tag: &str, code: &str) -> String {
+    record
+        .data_fields()
+        .find(|field| field.tag == tag)
+        .and_then(|field| field.subfield(code))
+        .map(|language| format!("{:<3.3}", language.trim()))
+        .unwrap_or_else(|| "und".to_string())
+}
+
+/// Builds a MARC 21 008 field from the dates of 100 `$a` and the language of the text (101).
+fn fixed_length_data(record: &RecordWrapper, data: &str) -> String {
+    let chars = data.chars().collect::<Vec<_>>();
+    let slice = |start: usize, length: usize| {
+        let value = chars.iter().skip(start).take(length).collect::<String>();
+        format!("{:<width$}", value, width = length)
+    };
+    let date_type = chars.get(8).copied().unwrap_or(' ');
+    let date_type = DATE_TYPES
+        .iter()
+        .find(|(unimarc, _)| *unimarc == date_type)
+        .map_or('|', |(_, marc21)| *marc21);
+    format!(
+        "{}{}{}{}xx {:17}{} d",
+        slice(2, 6),
+        date_type,
+        slice(9, 4),
+        slice(13, 4),
+        "",
+        subfield_language(record, "101", "a")
+    )
+}
+
+/// Builds UNIMARC 100 `$a` from the MARC 21 008 field. Only the year of the century is given in
+/// 008, years before 50 are taken as 20XX.
+fn general_processing_from(record: &RecordWrapper, data: &str) -> String {
+    let chars = data.chars().collect::<Vec<_>>();
+    let slice = |start: usize, length: usize| {
+        let value = chars.iter().skip(start).take(length).collect::<String>();
+        format!("{:<width$}", value, width = length)
+    };
+    let entered = slice(0, 6);
+    let century = if entered.as_str() < "50" { "20" } else { "19" };
+    let date_type = chars.get(6).copied().unwrap_or(' ');
+    let date_type = DATE_TYPES
+        .iter()
+        .find(|(_, marc21)| *marc21 == date_type)
+        .map_or('u', |(unimarc, _)| *unimarc);
+    let cataloguing_language = subfield_language(record, "040", "b");
+    format!(
+        "{}{}{}{}{}|||y0{}y50      ba",
+        century,
+        entered,
+        date_type,
+        slice(7, 4),
+        slice(11, 4),
+        cataloguing_language
+    )
+}
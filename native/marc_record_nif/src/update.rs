@@ -0,0 +1,124 @@
+/// Application of an update file, as sent by vendors, onto a base file. Each record of the
+/// update file is looked up in the base file by its key (001 or 035 `$a`), and its status
+/// (leader/05) tells what to do with it:
+///
+/// - a deleted record found in the base file removes the base record with the same key,
+/// - another record found in the base file replaces the base record with the same key,
+/// - another record not found in the base file is appended to it.
+///
+/// Deleted records not found in the base file are reported in `not_found`.
+///
+/// The records are copied as written, without being encoded again.
+use std::collections::HashMap;
+
+use rustler::{Encoder, Env, OwnedBinary, Term};
+
+use crate::options::{UpdateKey, UpdateOptions};
+use crate::RecordWrapper;
+
+pub struct Update {
+    /// The resulting file: the base records in their order, then the appended ones.
+    pub data: Vec<u8>,
+    pub replaced: Vec<String>,
+    pub deleted: Vec<String>,
+    pub added: Vec<String>,
+    /// The keys of the deleted records which are not in the base file.
+    pub not_found: Vec<String>,
+    /// The number of update records without key, which are ignored.
+    pub without_key: usize,
+}
+
+fn key(record: &RecordWrapper, key: UpdateKey) -> Option<String> {
+    let value = match key {
+        UpdateKey::ControlNumber => record.control_field("001"),
+        UpdateKey::SystemNumber => record
+            .data_fields()
+            .filter(|field| field.tag == "035")
+            .find_map(|field| field.subfield("a")),
+    };
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) fn apply(
+    base: &[(&[u8], RecordWrapper)],
+    updates: &[(&[u8], RecordWrapper)],
+    options: &UpdateOptions,
+) -> Update {
+    // The bytes of each base record, `None` once deleted.
+    let mut records = base
+        .iter()
+        .map(|(bytes, _)| Some(*bytes))
+        .collect::<Vec<_>>();
+    let mut positions = HashMap::new();
+    for (position, (_, record)) in base.iter().enumerate() {
+        if let Some(key) = key(record, options.key) {
+            positions.entry(key).or_insert(position);
+        }
+    }
+
+    let mut update = Update {
+        data: Vec::new(),
+        replaced: Vec::new(),
+        deleted: Vec::new(),
+        added: Vec::new(),
+        not_found: Vec::new(),
+        without_key: 0,
+    };
+    for (bytes, record) in updates {
+        let Some(key) = key(record, options.key) else {
+            update.without_key += 1;
+            continue;
+        };
+        let position = positions.get(&key).copied();
+        let is_deleted = record.leader.status().is_deleted();
+        match position {
+            Some(position) if is_deleted => {
+                records[position] = None;
+                positions.remove(&key);
+                update.deleted.push(key);
+            }
+            None if is_deleted => update.not_found.push(key),
+            Some(position) => {
+                records[position] = Some(*bytes);
+                update.replaced.push(key);
+            }
+            None => {
+                // A record appended by an earlier update can be corrected later in the file.
+                positions.insert(key.clone(), records.len());
+                records.push(Some(*bytes));
+                update.added.push(key);
+            }
+        }
+    }
+
+    update.data = records.into_iter().flatten().flatten().copied().collect();
+    update
+}
+
+impl Encoder for Update {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let mut data = OwnedBinary::new(self.data.len()).expect("Failed to allocate binary");
+        data.as_mut_slice().copy_from_slice(&self.data);
+        let summary = Term::map_from_pairs(
+            env,
+            &[
+                ("replaced", self.replaced.encode(env)),
+                ("deleted", self.deleted.encode(env)),
+                ("added", self.added.encode(env)),
+                ("not_found", self.not_found.encode(env)),
+                ("without_key", self.without_key.encode(env)),
+            ],
+        )
+        .expect("Failed to create map: duplicate key");
+        Term::map_from_pairs(
+            env,
+            &[
+                ("data", data.release(env).encode(env)),
+                ("summary", summary),
+            ],
+        )
+        .expect("Failed to create map: duplicate key")
+    }
+}
@@ -0,0 +1,398 @@
+/// Validation of decoded records against the MARC 21 Bibliographic format specification.
+///
+/// The checks rely on an embedded tag table describing, for each defined field, whether it is
+/// repeatable, which indicator values are allowed and which subfield codes are defined. Fields
+/// absent from the table (local `9XX` fields for example) are not checked.
+///
+/// Each problem found is reported as an `Issue` which is encoded as the Elixir tuple
+/// `{severity, tag, subfield, message}`.
+///
+/// Info about the format can be found here: https://www.loc.gov/marc/bibliographic/
+use rustler::{Encoder, Env, Term};
+
+use crate::{ControlFieldWrapper, DataFieldWrapper, FieldWrapper, RecordWrapper};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Encoder for Severity {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let severity = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        severity.encode(env)
+    }
+}
+
+pub struct Issue {
+    pub severity: Severity,
+    pub tag: Option<String>,
+    pub subfield: Option<String>,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn error(tag: &str, subfield: Option<&str>, message: String) -> Self {
+        Self::new(Severity::Error, Some(tag), subfield, message)
+    }
+
+    pub fn warning(tag: &str, subfield: Option<&str>, message: String) -> Self {
+        Self::new(Severity::Warning, Some(tag), subfield, message)
+    }
+
+    /// An issue concerning the whole record (leader, directory...) rather than a field.
+    pub fn record_issue(severity: Severity, message: String) -> Self {
+        Self::new(severity, None, None, message)
+    }
+
+    fn new(severity: Severity, tag: Option<&str>, subfield: Option<&str>, message: String) -> Self {
+        Issue {
+            severity,
+            tag: tag.map(str::to_string),
+            subfield: subfield.map(str::to_string),
+            message,
+        }
+    }
+}
+
+impl Encoder for Issue {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        (&self.severity, &self.tag, &self.subfield, &self.message).encode(env)
+    }
+}
+
+/// Validates a single record and returns every issue found, in field order.
+pub(crate) fn validate_record(record: &RecordWrapper) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut seen_tags: Vec<&str> = Vec::new();
+
+    for field in &record.fields {
+        let tag = match field {
+            FieldWrapper::Control(control) => {
+                validate_control_field(control, &mut issues);
+                control.tag.as_str()
+            }
+            FieldWrapper::Data(data) => {
+                validate_data_field(data, &mut issues);
+                data.tag.as_str()
+            }
+        };
+        if seen_tags.contains(&tag) && !is_repeatable(tag) {
+            issues.push(Issue::error(
+                tag,
+                None,
+                format!("field {} is not repeatable", tag),
+            ));
+        }
+        seen_tags.push(tag);
+    }
+
+    if !seen_tags.contains(&"245") {
+        issues.push(Issue::error(
+            "245",
+            None,
+            "missing title statement".to_string(),
+        ));
+    }
+    if !seen_tags.contains(&"008") {
+        issues.push(Issue::warning(
+            "008",
+            None,
+            "missing fixed-length data elements".to_string(),
+        ));
+    }
+
+    issues
+}
+
+fn validate_control_field(control: &ControlFieldWrapper, issues: &mut Vec<Issue>) {
+    let expected_length = match control.tag.as_str() {
+        "008" => Some((40, Severity::Error)),
+        "006" => Some((18, Severity::Warning)),
+        "005" => Some((16, Severity::Warning)),
+        _ => None,
+    };
+    if let Some((length, severity)) = expected_length {
+        let actual = control.data.chars().count();
+        if actual != length {
+            issues.push(Issue::new(
+                severity,
+                Some(&control.tag),
+                None,
+                format!(
+                    "field {} must be {} characters long, found {}",
+                    control.tag, length, actual
+                ),
+            ));
+        }
+    }
+}
+
+fn validate_data_field(data: &DataFieldWrapper, issues: &mut Vec<Issue>) {
+    let Some(definition) = find_definition(&data.tag) else {
+        return;
+    };
+    // Alternate graphic representations take the indicators and subfields of the linked field.
+    if definition.tag == "880" {
+        return;
+    }
+
+    let mut indicators = data.indicator.chars();
+    let positions = [
+        ("first", definition.first_indicator),
+        ("second", definition.second_indicator),
+    ];
+    for (position, allowed) in positions {
+        let value = indicators.next().unwrap_or(' ');
+        if !allowed.contains(value) {
+            issues.push(Issue::error(
+                &data.tag,
+                None,
+                format!("invalid {} indicator `{}`", position, value),
+            ));
+        }
+    }
+
+    for subfield in &data.subfields {
+        if !definition.subfields.contains(subfield.tag.as_str()) {
+            issues.push(Issue::error(
+                &data.tag,
+                Some(&subfield.tag),
+                format!(
+                    "subfield code `{}` is not defined for field {}",
+                    subfield.tag, data.tag
+                ),
+            ));
+        }
+    }
+}
+
+fn is_repeatable(tag: &str) -> bool {
+    find_definition(tag).is_none_or(|definition| definition.repeatable)
+}
+
+fn find_definition(tag: &str) -> Option<&'static TagDefinition> {
+    TAGS.iter().find(|definition| definition.tag == tag)
+}
+
+/// Definition of a field from the MARC 21 Bibliographic format. Indicators list every allowed
+/// character, a space standing for "blank". Control fields have no indicator nor subfield.
+struct TagDefinition {
+    tag: &'static str,
+    repeatable: bool,
+    first_indicator: &'static str,
+    second_indicator: &'static str,
+    subfields: &'static str,
+}
+
+const R: bool = true;
+const NR: bool = false;
+
+const fn tag(
+    tag: &'static str,
+    repeatable: bool,
+    first_indicator: &'static str,
+    second_indicator: &'static str,
+    subfields: &'static str,
+) -> TagDefinition {
+    TagDefinition {
+        tag,
+        repeatable,
+        first_indicator,
+        second_indicator,
+        subfields,
+    }
+}
+
+const LINKING_ENTRY: &str = "abcdeghikmnopqrstuwxyz4678";
+
+#[rustfmt::skip]
+const TAGS: &[TagDefinition] = &[
+    // Control fields
+    tag("001", NR, "", "", ""),
+    tag("003", NR, "", "", ""),
+    tag("005", NR, "", "", ""),
+    tag("006", R, "", "", ""),
+    tag("007", R, "", "", ""),
+    tag("008", NR, "", "", ""),
+    // Numbers and codes
+    tag("010", NR, " ", " ", "abz8"),
+    tag("013", R, " ", " ", "abcdef68"),
+    tag("015", R, " ", " ", "aqz268"),
+    tag("016", R, " 7", " ", "az268"),
+    tag("017", R, " ", " ", "abdiz268"),
+    tag("018", NR, " ", " ", "a68"),
+    tag("020", R, " ", " ", "acqz68"),
+    tag("022", R, " 01", " ", "almyz0168"),
+    tag("024", R, "0123478", " 01", "acdqz268"),
+    tag("025", R, " ", " ", "a8"),
+    tag("026", R, " ", " ", "abcde2568"),
+    tag("027", R, " ", " ", "aqz68"),
+    tag("028", R, "0123456", "0123", "abq68"),
+    tag("030", R, " ", " ", "az68"),
+    tag("032", R, " ", " ", "ab68"),
+    tag("033", R, " 012", " 012", "abc0123568"),
+    tag("034", R, "013", " 01", "abcdefghjkmnprstxyz012368"),
+    tag("035", R, " ", " ", "az068"),
+    tag("036", NR, " ", " ", "ab68"),
+    tag("037", R, " 23", " ", "abcfgn3568"),
+    tag("038", NR, " ", " ", "a68"),
+    tag("040", NR, " ", " ", "abcde68"),
+    tag("041", R, " 01", " 7", "abdefghijkmnpqrt2368"),
+    tag("042", NR, " ", " ", "a"),
+    tag("043", NR, " ", " ", "abc01268"),
+    tag("044", NR, " ", " ", "abc268"),
+    tag("045", NR, " 012", " ", "abc68"),
+    tag("046", R, " 123", " ", "abcdefjklmnop2368"),
+    tag("047", R, " ", " 7", "a28"),
+    tag("048", R, " ", " 7", "ab28"),
+    tag("050", R, " 01", "04", "ab0368"),
+    tag("051", R, " ", " ", "abc8"),
+    tag("052", R, " 17", " ", "abd012678"),
+    tag("055", R, " 01", "0123456789", "ab0268"),
+    tag("060", R, " 01", "04", "ab08"),
+    tag("066", NR, " ", " ", "abc"),
+    tag("070", R, "01", " ", "ab08"),
+    tag("072", R, " ", "07", "ax28"),
+    tag("074", R, " ", " ", "az8"),
+    tag("080", R, " 01", " ", "abx0268"),
+    tag("082", R, "017", " 04", "abmq268"),
+    tag("083", R, "017", " ", "acmqyz268"),
+    tag("084", R, " ", " ", "abq0268"),
+    tag("086", R, " 0", " ", "az0268"),
+    tag("088", R, " ", " ", "az68"),
+    // Main entries
+    tag("100", NR, "013", " ", "abcdefgjklnpqtu0124568"),
+    tag("110", NR, "012", " ", "abcdefgklnptu0124568"),
+    tag("111", NR, "012", " ", "acdefgjklnpqtu0124568"),
+    tag("130", NR, "0123456789", " ", "adfghklmnoprst0124568"),
+    // Titles
+    tag("210", R, "01", " 0", "ab268"),
+    tag("222", R, " ", "0123456789", "ab68"),
+    tag("240", NR, "01", "0123456789", "adfghklmnoprs0124568"),
+    tag("242", R, "01", "0123456789", "abchnpy68"),
+    tag("243", NR, "01", "0123456789", "adfghklmnoprs68"),
+    tag("245", NR, "01", "0123456789", "abcfghknps68"),
+    tag("246", R, "0123", " 012345678", "abfghinp568"),
+    tag("247", R, "01", "01", "abfghinpx68"),
+    // Edition, imprint
+    tag("250", R, " ", " ", "ab368"),
+    tag("254", NR, " ", " ", "a68"),
+    tag("255", R, " ", " ", "abcdefg68"),
+    tag("256", NR, " ", " ", "a68"),
+    tag("257", R, " ", " ", "a0268"),
+    tag("258", R, " ", " ", "ab68"),
+    tag("260", R, " 23", " ", "abcefg368"),
+    tag("263", NR, " ", " ", "a68"),
+    tag("264", R, " 23", "01234", "abc368"),
+    tag("270", R, " 12", " 07", "abcdefghijklmnpqrz468"),
+    // Physical description
+    tag("300", R, " ", " ", "abcefg368"),
+    tag("306", NR, " ", " ", "a68"),
+    tag("310", NR, " ", " ", "ab068"),
+    tag("321", R, " ", " ", "ab068"),
+    tag("336", R, " ", " ", "ab0123568"),
+    tag("337", R, " ", " ", "ab0123568"),
+    tag("338", R, " ", " ", "ab0123568"),
+    tag("340", R, " ", " ", "abcdefghijkmnopq0123568"),
+    tag("344", R, " ", " ", "abcdefgh0123568"),
+    tag("347", R, " ", " ", "abcdef0123568"),
+    tag("362", R, "01", " ", "az68"),
+    tag("380", R, " ", " ", "a01234568"),
+    // Series statement
+    tag("490", R, "01", " ", "alvxy3568"),
+    // Notes
+    tag("500", R, " ", " ", "a3568"),
+    tag("501", R, " ", " ", "a568"),
+    tag("502", R, " ", " ", "abcdgo68"),
+    tag("504", R, " ", " ", "ab68"),
+    tag("505", R, "0128", " 0", "agrtu68"),
+    tag("506", R, " 01", " ", "abcdefgqu23568"),
+    tag("508", R, " ", " ", "a68"),
+    tag("510", R, "01234", " ", "abcux368"),
+    tag("511", R, "01", " ", "a68"),
+    tag("515", R, " ", " ", "a68"),
+    tag("518", R, " ", " ", "adop0123568"),
+    tag("520", R, " 0123478", " ", "abcu23568"),
+    tag("521", R, " 012348", " ", "ab368"),
+    tag("524", R, " 8", " ", "a2368"),
+    tag("530", R, " ", " ", "abcdu368"),
+    tag("533", R, " ", " ", "abcdefmn35678"),
+    tag("534", R, " ", " ", "abcefklmnoptxz368"),
+    tag("536", R, " ", " ", "abcdefgh68"),
+    tag("538", R, " ", " ", "aiu3568"),
+    tag("540", R, " ", " ", "abcdfgqu23568"),
+    tag("541", R, " 01", " ", "abcdefhno3568"),
+    tag("542", R, " 01", " ", "abcdefghijklmnopqrsu368"),
+    tag("545", R, " 01", " ", "abu68"),
+    tag("546", R, " ", " ", "ab368"),
+    tag("550", R, " ", " ", "a68"),
+    tag("555", R, " 08", " ", "abcdu368"),
+    tag("561", R, " 01", " ", "au3568"),
+    tag("580", R, " ", " ", "a68"),
+    tag("583", R, " 01", " ", "abcdefhijklnouxz23568"),
+    tag("586", R, " 8", " ", "a368"),
+    tag("588", R, " 01", " ", "a568"),
+    // Subject access
+    tag("600", R, "013", "01234567", "abcdefghjklmnopqrstuvxyz0123468"),
+    tag("610", R, "012", "01234567", "abcdefghklmnoprstuvxyz0123468"),
+    tag("611", R, "012", "01234567", "acdefghjklnpqstuvxyz0123468"),
+    tag("630", R, "0123456789", "01234567", "adefghklmnoprstvxyz0123468"),
+    tag("647", R, " ", "01234567", "acdgvxyz012368"),
+    tag("648", R, " ", "01234567", "avxyz012368"),
+    tag("650", R, " 012", "01234567", "abcdegvxyz0123468"),
+    tag("651", R, " ", "01234567", "aegvxyz0123468"),
+    tag("653", R, " 012", " 0123456", "a68"),
+    tag("654", R, " 012", " ", "abcevyz0123468"),
+    tag("655", R, " 0", "01234567", "abcvxyz0123568"),
+    tag("656", R, " ", "7", "akvxyz012368"),
+    tag("657", R, " ", "7", "avxyz012368"),
+    tag("658", R, " ", " ", "abcd268"),
+    tag("662", R, " ", " ", "abcdefgh012468"),
+    // Added entries
+    tag("700", R, "013", " 2", "abcdefghijklmnopqrstux01234568"),
+    tag("710", R, "012", " 2", "abcdefghiklmnoprstux01234568"),
+    tag("711", R, "012", " 2", "acdefghijklnpqstux01234568"),
+    tag("720", R, " 12", " ", "ae0468"),
+    tag("730", R, "0123456789", " 2", "adfghiklmnoprstx01234568"),
+    tag("740", R, "0123456789", " 2", "ahnp568"),
+    tag("751", R, " ", " ", "aeg0123468"),
+    tag("752", R, " ", " ", "abcdefgh012468"),
+    tag("753", R, " ", " ", "abc012368"),
+    tag("758", R, " ", " ", "ai01234568"),
+    // Linking entries
+    tag("760", R, "01", " 8", LINKING_ENTRY),
+    tag("762", R, "01", " 8", LINKING_ENTRY),
+    tag("765", R, "01", " 8", LINKING_ENTRY),
+    tag("767", R, "01", " 8", LINKING_ENTRY),
+    tag("770", R, "01", " 8", LINKING_ENTRY),
+    tag("772", R, "01", " 08", LINKING_ENTRY),
+    tag("773", R, "01", " 8", LINKING_ENTRY),
+    tag("774", R, "01", " 8", LINKING_ENTRY),
+    tag("775", R, "01", " 8", LINKING_ENTRY),
+    tag("776", R, "01", " 8", LINKING_ENTRY),
+    tag("777", R, "01", " 8", LINKING_ENTRY),
+    tag("780", R, "01", "01234567", LINKING_ENTRY),
+    tag("785", R, "01", "012345678", LINKING_ENTRY),
+    tag("786", R, "01", " 8", LINKING_ENTRY),
+    tag("787", R, "01", " 8", LINKING_ENTRY),
+    // Series added entries
+    tag("800", R, "013", " ", "abcdefghjklmnopqrstuvwx012345678"),
+    tag("810", R, "012", " ", "abcdefghklmnoprstuvwx012345678"),
+    tag("811", R, "012", " ", "acdefghjklnpqstuvwx012345678"),
+    tag("830", R, " ", "0123456789", "adfghklmnoprstvwx0123568"),
+    // Holdings, location, alternate graphics
+    tag("850", R, " ", " ", "a8"),
+    tag("852", R, " 012345678", " 012", "abcdefghijklmnpqstuxz2368"),
+    tag("856", R, " 012347", " 01278", "abcdfhijklmnopqrstuvwxyz235678"),
+    tag("880", R, "", "", ""),
+    tag("882", NR, " ", " ", "aiw68"),
+    tag("883", R, " 01", " ", "acdqrwx0158"),
+    tag("884", R, " ", " ", "agkqu"),
+    tag("886", R, "012", " ", "abcdefghijklmnopqrstuvwxyz0123456789"),
+    tag("887", R, " ", " ", "a2"),
+];
@@ -0,0 +1,57 @@
+/// Writes records in the ISO 2709 exchange format, the inverse of `raw::split_records` and
+/// `RawRecord::parse`. The record length and the base address of the leader are computed, the
+/// other leader positions are written as given.
+use crate::raw::{
+    DIRECTORY_ENTRY_LENGTH, FIELD_TERMINATOR, LEADER_LENGTH, RECORD_TERMINATOR, SUBFIELD_DELIMITER,
+};
+use crate::FieldWrapper;
+
+/// Writes one record. `leader` must be 24 bytes long; the fields are written in the given order.
+pub(crate) fn write_record(leader: &[u8], fields: &[FieldWrapper]) -> Result<Vec<u8>, String> {
+    if leader.len() != LEADER_LENGTH {
+        return Err(format!(
+            "leader is {} bytes long instead of {}",
+            leader.len(),
+            LEADER_LENGTH
+        ));
+    }
+    let mut directory = Vec::with_capacity(fields.len() * DIRECTORY_ENTRY_LENGTH + 1);
+    let mut data = Vec::new();
+    for field in fields {
+        let (tag, body) = match field {
+            FieldWrapper::Control(control) => (&control.tag, control.data.as_bytes().to_vec()),
+            FieldWrapper::Data(field) => {
+                let mut body = field.indicator.as_bytes().to_vec();
+                for subfield in &field.subfields {
+                    body.push(SUBFIELD_DELIMITER);
+                    body.extend_from_slice(subfield.tag.as_bytes());
+                    body.extend_from_slice(subfield.data.as_bytes());
+                }
+                (&field.tag, body)
+            }
+        };
+        let length = body.len() + 1;
+        if tag.len() != 3 || length > 9999 || data.len() > 99999 {
+            return Err(format!("field {} does not fit in a directory entry", tag));
+        }
+        directory.extend_from_slice(format!("{}{:04}{:05}", tag, length, data.len()).as_bytes());
+        data.extend_from_slice(&body);
+        data.push(FIELD_TERMINATOR);
+    }
+    directory.push(FIELD_TERMINATOR);
+
+    let base_address = LEADER_LENGTH + directory.len();
+    let record_length = base_address + data.len() + 1;
+    if record_length > 99999 {
+        return Err(format!("record is {} bytes long", record_length));
+    }
+    let mut record = Vec::with_capacity(record_length);
+    record.extend_from_slice(format!("{:05}", record_length).as_bytes());
+    record.extend_from_slice(&leader[5..12]);
+    record.extend_from_slice(format!("{:05}", base_address).as_bytes());
+    record.extend_from_slice(&leader[17..]);
+    record.extend_from_slice(&directory);
+    record.extend_from_slice(&data);
+    record.push(RECORD_TERMINATOR);
+    Ok(record)
+}
@@ -0,0 +1,86 @@
+defmodule MarcRecordAuthorityTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/authority.mrc")
+
+    {:ok,
+     records: MarcRecord.parse_records_wrapper(data),
+     authorities: MarcRecord.authority_records_wrapper(data)}
+  end
+
+  test "authority records are parsed with the authority record type", state do
+    record_types = Enum.map(state[:records], fn record -> record["leader"]["record_type"] end)
+    assert record_types == ["authority", "authority", "authority"]
+  end
+
+  test "authority status and encoding level are decoded", state do
+    leader =
+      state[:records]
+      |> Enum.at(2)
+      |> Map.get("leader")
+
+    assert leader["status"] == "deleted_heading_replaced"
+    assert leader["encoding_level"] == "incomplete_authority"
+  end
+
+  test "record 1: heading comes from the 1XX field", state do
+    heading =
+      state[:authorities]
+      |> Enum.at(0)
+      |> Map.fetch!("heading")
+
+    assert heading == %{"tag" => "100", "heading" => "Twain, Mark, 1835-1910", "relationship" => nil}
+  end
+
+  test "record 1: see from and see also tracings", state do
+    authority = Enum.at(state[:authorities], 0)
+
+    assert Enum.map(authority["see_from"], fn heading -> heading["heading"] end) == [
+             "Clemens, Samuel Langhorne, 1835-1910",
+             "Snodgrass, Quintus Curtius, 1835-1910"
+           ]
+
+    assert [%{"heading" => "Conte, Louis de, 1835-1910", "relationship" => "Alternate identity:"}] =
+             authority["see_also"]
+  end
+
+  test "record 2: subdivisions are separated by dashes", state do
+    authority = Enum.at(state[:authorities], 1)
+    assert authority["heading"]["heading"] == "Cats--Behavior"
+  end
+
+  test "record 1: 008 is decoded with the authority layout", state do
+    fixed = Enum.at(state[:authorities], 0)["fixed_length_data"]
+
+    assert fixed["date_entered_on_file"] == "790607"
+    assert fixed["kind_of_record"] == "established_heading"
+    assert fixed["descriptive_cataloging_rules"] == "aacr2"
+    assert fixed["subject_heading_system"] == "library_of_congress_subject_headings"
+    assert fixed["level_of_establishment"] == "fully_established"
+  end
+
+  test "bibliographic records are not returned as authorities" do
+    authorities =
+      File.read!("samples/marc8_multiple.mrc")
+      |> MarcRecord.authority_records_wrapper()
+
+    assert authorities == []
+  end
+
+  test "authority values are not accepted in bibliographic records" do
+    <<head::binary-size(5), _status::binary-size(1), rest::binary>> =
+      File.read!("samples/update_base.mrc")
+
+    {status, _} = MarcRecord.parse_records_wrapper(head <> "x" <> rest)
+    assert status == :error
+  end
+
+  test "invalid leader values of bibliographic records are rejected" do
+    <<head::binary-size(6), _record_type::binary-size(1), rest::binary>> =
+      File.read!("samples/update_base.mrc")
+
+    {status, _} = MarcRecord.parse_records_wrapper(head <> "q" <> rest)
+    assert status == :error
+  end
+end
@@ -0,0 +1,81 @@
+defmodule MarcRecordBibframeTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/marc8_multiple.mrc")
+
+    {:ok,
+     turtle: MarcRecord.to_bibframe_turtle_wrapper(data),
+     ntriples: MarcRecord.to_bibframe_ntriples_wrapper(data)}
+  end
+
+  @work "<http://example.org/B301882#Work>"
+  @instance "<http://example.org/B301882#Instance>"
+  @bf "http://id.loc.gov/ontologies/bibframe/"
+  @rdf_type "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>"
+
+  test "one graph per record", state do
+    assert length(state[:turtle]) == 109
+    assert length(state[:ntriples]) == 109
+  end
+
+  test "record 1: Work and Instance classes come from leader/06 and 008", state do
+    triples = String.split(Enum.at(state[:ntriples], 0), "\n", trim: true)
+
+    assert "#{@work} #{@rdf_type} <#{@bf}Work> ." in triples
+    assert "#{@work} #{@rdf_type} <#{@bf}Text> ." in triples
+    assert "#{@instance} #{@rdf_type} <#{@bf}Instance> ." in triples
+    assert "#{@instance} #{@rdf_type} <#{@bf}Electronic> ." in triples
+    assert "#{@work} <#{@bf}hasInstance> #{@instance} ." in triples
+    assert "#{@instance} <#{@bf}instanceOf> #{@work} ." in triples
+
+    assert "#{@instance} <#{@bf}issuance> <http://id.loc.gov/vocabulary/issuance/mono> ." in triples
+  end
+
+  test "record 1: titles, subjects and identifiers", state do
+    ntriples = Enum.at(state[:ntriples], 0)
+
+    assert ntriples =~ ~s(<#{@bf}mainTitle> "Théories économiques en 30 secondes" .)
+    assert ntriples =~ ~s(<http://www.w3.org/2000/01/rdf-schema#label> "Économie politique--Ouvrages de vulgarisation" .)
+    assert ntriples =~ ~s(<http://www.w3.org/1999/02/22-rdf-syntax-ns#value> "9782896479764" .)
+
+    assert ntriples =~
+             "#{@work} <#{@bf}language> <http://id.loc.gov/vocabulary/languages/fre> ."
+  end
+
+  test "record 1: 856 becomes an Item of the Instance", state do
+    ntriples = Enum.at(state[:ntriples], 0)
+
+    assert ntriples =~ "<http://example.org/B301882#Item1> <#{@bf}itemOf> #{@instance} ."
+
+    assert ntriples =~
+             "<#{@bf}electronicLocator> <https://cssdm.biblius.ca/explore/products/9782896479764> ."
+  end
+
+  test "Turtle uses prefixes and groups the statements by subject", state do
+    turtle = Enum.at(state[:turtle], 0)
+
+    assert String.starts_with?(turtle, "@prefix bf: <#{@bf}> .\n")
+    assert turtle =~ "<http://example.org/B301882#Work>\n    a bf:Work ;\n    a bf:Text ;"
+    assert turtle =~ ~s(bf:mainTitle "Théories économiques en 30 secondes" ;)
+  end
+
+  test "every N-Triples line is a complete statement", state do
+    for ntriples <- state[:ntriples], line <- String.split(ntriples, "\n", trim: true) do
+      assert String.ends_with?(line, " .")
+    end
+  end
+
+  test "blank node labels are unique across records", state do
+    labels =
+      state[:ntriples]
+      |> Enum.with_index(1)
+      |> Enum.map(fn {ntriples, number} ->
+        labels = ~r/_:\w+/ |> Regex.scan(ntriples) |> List.flatten() |> MapSet.new()
+        assert Enum.all?(labels, &String.starts_with?(&1, "_:r#{number}b"))
+        labels
+      end)
+
+    assert Enum.sum(Enum.map(labels, &MapSet.size/1)) == MapSet.size(Enum.reduce(labels, &MapSet.union/2))
+  end
+end
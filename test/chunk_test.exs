@@ -0,0 +1,59 @@
+defmodule MarcRecordChunkTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, data: File.read!("samples/marc8_multiple.mrc")}
+  end
+
+  test "chunks hold whole records", %{data: data} do
+    chunks = MarcRecord.chunk_records_wrapper(data, 10)
+
+    assert length(chunks) == 11
+    assert IO.iodata_to_binary(chunks) == data
+
+    counts = Enum.map(chunks, fn chunk -> length(MarcRecord.parse_records_wrapper(chunk)) end)
+
+    assert Enum.sum(counts) == 109
+    assert List.last(counts) == 9
+  end
+
+  test "chunks are parsed like the whole file", %{data: data} do
+    records =
+      data
+      |> MarcRecord.chunk_records_wrapper(25)
+      |> Enum.flat_map(&MarcRecord.parse_records_wrapper/1)
+
+    assert records == MarcRecord.parse_records_wrapper(data)
+  end
+
+  test "record ranges", %{data: data} do
+    ranges = MarcRecord.record_ranges("samples/marc8_multiple.mrc")
+
+    assert length(ranges) == 109
+    assert hd(ranges) == {0, String.to_integer(binary_part(data, 0, 5))}
+
+    Enum.reduce(ranges, 0, fn {offset, length}, expected_offset ->
+      assert offset == expected_offset
+      assert :binary.last(binary_part(data, offset, length)) == 0x1D
+      offset + length
+    end)
+  end
+
+  test "a stray record terminator does not split a record", %{data: data} do
+    damaged = :binary.replace(data, "Hurtubise", <<0x1D, "urtubise">>)
+
+    assert MarcRecord.record_ranges_wrapper(damaged, 1) ==
+             MarcRecord.record_ranges_wrapper(data, 1)
+
+    chunks = MarcRecord.chunk_records_wrapper(damaged, 10)
+
+    assert length(chunks) == 11
+
+    assert Enum.flat_map(chunks, &MarcRecord.parse_records_wrapper/1) ==
+             MarcRecord.parse_records_wrapper(damaged)
+  end
+
+  test "the chunk size must be positive", %{data: data} do
+    assert_raise ArgumentError, fn -> MarcRecord.chunk_records_wrapper(data, 0) end
+  end
+end
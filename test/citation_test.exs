@@ -0,0 +1,73 @@
+defmodule MarcRecordCitationTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/marc8_multiple.mrc")
+
+    {:ok,
+     ris: MarcRecord.to_ris_wrapper(data),
+     bibtex: MarcRecord.to_bibtex_wrapper(data),
+     csl_json: MarcRecord.to_csl_json_wrapper(data)}
+  end
+
+  test "record 1: RIS", state do
+    lines = String.split(Enum.at(state[:ris], 0), "\n", trim: true)
+
+    assert hd(lines) == "TY  - BOOK"
+    assert "AU  - Marron, Donald B." in lines
+    assert "AU  - Fishwick, Adam" in lines
+    assert "PY  - 2011" in lines
+    assert "PB  - Hurtubise" in lines
+    assert "SN  - 9782896479764" in lines
+    assert List.last(lines) == "ER  - "
+  end
+
+  test "record 1: BibTeX", state do
+    bibtex = Enum.at(state[:bibtex], 0)
+
+    assert String.starts_with?(bibtex, "@book{B301882,\n")
+    assert bibtex =~ "  author = {Marron, Donald B. and Fishwick, Adam},\n"
+    assert bibtex =~ "  year = {2011},\n"
+    assert bibtex =~ "  address = {Montréal},\n"
+  end
+
+  test "BibTeX: serials are misc entries" do
+    <<head::binary-size(7), _level, rest::binary>> = File.read!("samples/marc8_multiple.mrc")
+
+    bibtex = hd(MarcRecord.to_bibtex_wrapper(<<head::binary, "s", rest::binary>>))
+
+    assert String.starts_with?(bibtex, "@misc{B301882,\n")
+  end
+
+  test "BibTeX: the key falls back to the record number" do
+    bibtex =
+      File.read!("samples/marc8_multiple.mrc")
+      |> :binary.replace("B301882", "[#####]")
+      |> MarcRecord.to_bibtex_wrapper()
+      |> hd()
+
+    assert String.starts_with?(bibtex, "@book{record1,\n")
+  end
+
+  test "record 1: CSL-JSON", state do
+    csl_json = Enum.at(state[:csl_json], 0)
+
+    assert csl_json =~ ~s("type": "book")
+    assert csl_json =~ ~s("family": "Marron",\n      "given": "Donald B.")
+    assert csl_json =~ ~s("date-parts": [\n      [\n        2011\n      ]\n    ])
+    assert csl_json =~ ~s("ISBN": "9782896479764")
+  end
+
+  test "every RIS record is closed", state do
+    assert Enum.all?(state[:ris], &String.ends_with?(&1, "ER  - \n"))
+  end
+
+  test "URLs are exported whole, trailing slash included" do
+    [ris] =
+      File.read!("samples/merge_master.mrc")
+      |> :binary.replace("example.com/9782896479764", "example.com/978289647976/")
+      |> MarcRecord.to_ris_wrapper()
+
+    assert "UR  - http://old.example.com/978289647976/" in String.split(ris, "\n", trim: true)
+  end
+end
@@ -0,0 +1,76 @@
+defmodule MarcRecordDedupeTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok,
+     batches: [
+       File.read!("samples/marc8_multiple.mrc"),
+       File.read!("samples/vendor_duplicates.mrc")
+     ]}
+  end
+
+  defp control_numbers(cluster) do
+    Enum.map(cluster["records"], fn record -> record["control_number"] end)
+  end
+
+  test "match keys are normalized" do
+    [keys | _] = MarcRecord.match_keys_wrapper(File.read!("samples/vendor_duplicates.mrc"))
+
+    assert keys["isbns"] == ["9782896479764"]
+    assert keys["title"] == "theories economiques en trente secondes"
+    assert keys["author"] == "marron"
+    assert keys["year"] == "2011"
+    assert keys["fuzzy"] == "theories economiques en trente/marron/2011"
+  end
+
+  test "OCLC numbers lose their prefix and leading zeros" do
+    keys = MarcRecord.match_keys_wrapper(File.read!("samples/vendor_duplicates.mrc"))
+
+    assert Enum.map(Enum.slice(keys, 2, 2), fn keys -> keys["oclc_numbers"] end) == [
+             ["12345"],
+             ["12345"]
+           ]
+  end
+
+  test "duplicates are grouped across batches", state do
+    clusters = MarcRecord.dedupe_wrapper(state[:batches], [])
+
+    assert Enum.map(clusters, &control_numbers/1) == [
+             ["B301882", "VND-1"],
+             ["B314284", "VND-2"],
+             ["VND-3", "VND-4"]
+           ]
+
+    assert Enum.map(clusters, fn cluster -> cluster["matched_on"] end) == [
+             ["isbn"],
+             ["title_author"],
+             ["oclc", "title_author"]
+           ]
+
+    assert Enum.map(clusters, fn cluster -> cluster["confidence"] end) == [0.95, 0.8, 1.0]
+    assert hd(hd(clusters)["records"]) == %{
+             "batch" => 0,
+             "position" => 0,
+             "control_number" => "B301882"
+           }
+  end
+
+  test "records without an author are not matched on their title alone" do
+    base = File.read!("samples/update_base.mrc")
+
+    assert MarcRecord.dedupe_wrapper([base, base], strategies: [:title_author]) == []
+    assert length(MarcRecord.dedupe_wrapper([base, base], strategies: [:oclc])) == 3
+  end
+
+  test "the strategies can be chosen", state do
+    clusters = MarcRecord.dedupe_wrapper(state[:batches], strategies: [:isbn])
+
+    assert Enum.map(clusters, &control_numbers/1) == [["B301882", "VND-1"]]
+  end
+
+  test "unknown options are rejected", state do
+    assert_raise ArgumentError, fn ->
+      MarcRecord.dedupe_wrapper(state[:batches], strategies: [:lccn])
+    end
+  end
+end
@@ -0,0 +1,68 @@
+defmodule MarcRecordDiffTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok,
+     diff:
+       MarcRecord.diff_records("samples/merge_master.mrc", "samples/merge_master_corrected.mrc")}
+  end
+
+  defp change(diff, tag, occurrence) do
+    Enum.find(diff["fields"], fn field ->
+      field["tag"] == tag and field["occurrence"] == occurrence
+    end)
+  end
+
+  test "leader positions", %{diff: diff} do
+    assert diff["identical"] == false
+    assert diff["leader"] == [%{"position" => 17, "from" => "8", "to" => "5"}]
+  end
+
+  test "added and removed fields", %{diff: diff} do
+    assert change(diff, "500", 1)["change"] == "added"
+    assert change(diff, "500", 1)["to"] == "=500  \\\\$aTraduction de: 30-second economics."
+    assert change(diff, "856", 1)["change"] == "removed"
+    assert change(diff, "856", 1)["to"] == nil
+    assert change(diff, "650", 1) == nil
+  end
+
+  test "changed subfields and indicators", %{diff: diff} do
+    assert change(diff, "245", 1)["subfields"] == [
+             %{
+               "code" => "c",
+               "occurrence" => 1,
+               "change" => "added",
+               "from" => nil,
+               "to" => "Donald Marron."
+             }
+           ]
+
+    assert change(diff, "245", 1)["indicators"] == nil
+    assert change(diff, "650", 2)["indicators"] == %{"from" => " 0", "to" => " 7"}
+  end
+
+  test "unified rendering", %{diff: diff} do
+    lines = String.split(diff["unified"], "\n", trim: true)
+
+    assert " =001  MASTER-1" in lines
+    assert "-=856  40$uhttp://old.example.com/9782896479764" in lines
+    assert "+=650  \\7$aEconomic history.$2rvm" in lines
+    assert Enum.count(lines, &String.starts_with?(&1, "+")) == 4
+  end
+
+  test "identical records" do
+    diff = MarcRecord.diff_records("samples/merge_master.mrc", "samples/merge_master.mrc")
+
+    assert diff["identical"] == true
+    assert diff["fields"] == []
+  end
+
+  test "only the first record of each binary is decoded" do
+    master = File.read!("samples/merge_master.mrc")
+    damaged = master <> "00042nam  2200025 i 4500" <> <<0x1D>>
+
+    diff = MarcRecord.diff_records_wrapper(damaged, master)
+
+    assert diff["identical"] == true
+  end
+end
@@ -0,0 +1,46 @@
+defmodule MarcRecordDublinCoreTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/marc8_multiple.mrc")
+
+    {:ok,
+     dublin_core: MarcRecord.to_dublin_core_wrapper(data),
+     oai_dc: MarcRecord.to_oai_dc_wrapper(data)}
+  end
+
+  test "one Dublin Core description per record", state do
+    assert length(state[:dublin_core]) == 109
+    assert length(state[:oai_dc]) == 109
+  end
+
+  test "record 1: elements are mapped following the LoC crosswalk", state do
+    dc = Enum.at(state[:dublin_core], 0)
+
+    assert dc["title"] == [
+             "Théories économiques en 30 secondes : les 50 théories économiques les plus marquantes, expliquées en moins d'une minute"
+           ]
+
+    assert dc["creator"] == ["Marron, Donald B. (Baird)", "Fishwick, Adam"]
+    assert dc["subject"] == ["Économie politique--Ouvrages de vulgarisation"]
+    assert dc["publisher"] == ["Montréal : Hurtubise"]
+    assert dc["date"] == ["2011"]
+    assert dc["type"] == ["Text", "Livres numériques"]
+    assert "9782896479764" in dc["identifier"]
+    assert dc["language"] == ["fre"]
+  end
+
+  test "record 2: values repeated in several fields appear once", state do
+    types = Enum.at(state[:dublin_core], 1)["type"]
+
+    assert types == Enum.uniq(types)
+  end
+
+  test "record 1: oai_dc XML is escaped", state do
+    xml = Enum.at(state[:oai_dc], 0)
+
+    assert String.starts_with?(xml, "<oai_dc:dc xmlns:oai_dc=\"http://www.openarchives.org/OAI/2.0/oai_dc/\"")
+    assert xml =~ "<dc:date>2011</dc:date>"
+    assert xml =~ "moins d&apos;une minute</dc:title>"
+  end
+end
@@ -0,0 +1,58 @@
+defmodule MarcRecordHoldingsTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/holdings.mrc")
+
+    {:ok,
+     records: MarcRecord.parse_records_wrapper(data),
+     holdings: MarcRecord.holdings_records_wrapper(data)}
+  end
+
+  test "holdings records are parsed with their record type and encoding level", state do
+    leaders = Enum.map(state[:records], fn record -> record["leader"] end)
+
+    assert Enum.map(leaders, fn leader -> leader["record_type"] end) == [
+             "serial_item_holdings",
+             "single_part_item_holdings"
+           ]
+
+    assert Enum.map(leaders, fn leader -> leader["encoding_level"] end) == [
+             "holdings_level_3",
+             "holdings_level_1"
+           ]
+  end
+
+  test "record 1: 852 location is decoded", state do
+    [location] = Enum.at(state[:holdings], 0)["locations"]
+
+    assert location["institution"] == "QMBN"
+    assert location["shelving_location"] == "Periodicals"
+    assert location["call_number"] == "QH1 .N38"
+    assert location["public_note"] == "Current issues in reading room"
+  end
+
+  test "record 1: 853/863 pairs are expanded into summary holdings statements", state do
+    assert Enum.at(state[:holdings], 0)["summary_holdings"] == [
+             "v.1:no.1 (1990:Jan.)-v.5:no.12 (1994:Dec.)",
+             "v.7 (1996)-",
+             "suppl.1 (1992)"
+           ]
+  end
+
+  test "record 1: 008 is decoded with the holdings layout", state do
+    holdings = Enum.at(state[:holdings], 0)
+
+    assert holdings["linked_record_number"] == "b0012345"
+    assert holdings["fixed_length_data"]["receipt_or_acquisition_status"] == "currently_received"
+    assert holdings["fixed_length_data"]["method_of_acquisition"] == "purchase"
+    assert holdings["fixed_length_data"]["general_retention_policy"] == "permanently_retained"
+  end
+
+  test "record 2: no summary holdings without captions", state do
+    holdings = Enum.at(state[:holdings], 1)
+
+    assert holdings["summary_holdings"] == []
+    assert [%{"copy_number" => "2", "sublocation" => "Annex"}] = holdings["locations"]
+  end
+end
@@ -0,0 +1,55 @@
+defmodule MarcRecordLinkageTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/linked_fields.mrc")
+
+    {:ok,
+     records: MarcRecord.parse_records_wrapper(data, link_fields: true),
+     unlinked: MarcRecord.parse_records_wrapper(data)}
+  end
+
+  defp field(record, tag) do
+    Enum.find(record["fields"], fn field -> field["tag"] == tag end)
+  end
+
+  defp subfield(field, code) do
+    Enum.find_value(field["subfields"], fn subfield ->
+      if subfield["tag"] == code, do: subfield["data"]
+    end)
+  end
+
+  test "the 880 fields are returned within their regular field", state do
+    title = field(Enum.at(state[:records], 0), "245")
+
+    assert subfield(title, "a") == "Noruwei no mori /"
+    assert title["linked"]["tag"] == "880"
+    assert subfield(title["linked"], "a") == "ノルウェイの森 /"
+    assert subfield(title["linked"], "6") == "245-02/$1"
+    assert title["script"] == "cjk"
+    refute title["right_to_left"]
+  end
+
+  test "an 880 without a regular field is kept", state do
+    fields = Enum.at(state[:records], 0)["fields"]
+
+    assert [unpaired] = Enum.filter(fields, fn field -> field["tag"] == "880" end)
+    assert subfield(unpaired, "6") == "500-00/$1"
+  end
+
+  test "the script and orientation of Arabic", state do
+    title = field(Enum.at(state[:records], 1), "245")
+
+    assert subfield(title["linked"], "a") == "الكتاب."
+    assert title["script"] == "arabic"
+    assert title["right_to_left"]
+    assert field(Enum.at(state[:records], 1), "880") == nil
+  end
+
+  test "the fields are not linked by default", state do
+    record = Enum.at(state[:unlinked], 0)
+
+    assert length(Enum.filter(record["fields"], fn field -> field["tag"] == "880" end)) == 4
+    refute Map.has_key?(field(record, "245"), "linked")
+  end
+end
@@ -136,4 +136,16 @@ defmodule MarcRecordTest do
              elem(Map.fetch(keys, key), 1) == value
            end)
   end
+
+  test "a record terminator inside field data does not split the record" do
+    data = File.read!("samples/marc8_multiple.mrc")
+
+    records =
+      data
+      |> :binary.replace("Hurtubise", <<0x1D, "urtubise">>)
+      |> MarcRecord.parse_records_wrapper()
+
+    assert length(records) == 109
+    assert Enum.drop(records, 1) == Enum.drop(MarcRecord.parse_records_wrapper(data), 1)
+  end
 end
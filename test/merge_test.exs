@@ -0,0 +1,76 @@
+defmodule MarcRecordMergeTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok,
+     master: File.read!("samples/merge_master.mrc"),
+     incoming: File.read!("samples/merge_vendor.mrc")}
+  end
+
+  defp field_data(record, tag) do
+    record["fields"]
+    |> Enum.filter(fn field -> field["tag"] == tag end)
+    |> Enum.map(fn field -> Enum.map(field["subfields"], fn subfield -> subfield["data"] end) end)
+    |> Enum.sort()
+  end
+
+  defp changes(merge, tag) do
+    merge["changes"]
+    |> Enum.filter(fn change -> change["tag"] == tag end)
+    |> Enum.map(fn change -> {change["action"], change["field"]} end)
+  end
+
+  test "default policies", %{master: master, incoming: incoming} do
+    %{"record" => record} = merge = MarcRecord.merge_records_wrapper(master, incoming, [])
+
+    assert Enum.find(record["fields"], fn field -> field["tag"] == "001" end)["data"] ==
+             "MASTER-1"
+
+    assert field_data(record, "949") == [["HB171 .M37 2011", "Main stacks"]]
+    assert field_data(record, "856") == [["https://vendor.example.com/9782896479764"]]
+    assert field_data(record, "650") == [["Economic history."], ["Economics."], ["Finance."]]
+    assert changes(merge, "650") == [{"added", "=650  \\0$aFinance."}]
+    assert changes(merge, "949") == []
+  end
+
+  test "the more complete record wins the tags without policy", %{
+    master: master,
+    incoming: incoming
+  } do
+    %{"record" => record} = merge = MarcRecord.merge_records_wrapper(master, incoming, [])
+
+    assert record["leader"]["encoding_level"] == "full"
+    assert field_data(record, "300") == [["160 p. :", "ill. ;", "22 cm"]]
+    assert Enum.all?(changes(merge, "245"), fn {_, field} -> field =~ "=245  10" end)
+
+    %{"record" => record} = MarcRecord.merge_records_wrapper(incoming, master, [])
+
+    assert field_data(record, "300") == [["160 p. :", "ill. ;", "22 cm"]]
+    assert field_data(record, "245") == [
+             ["Théories économiques en trente secondes /", "Donald Marron."]
+           ]
+  end
+
+  test "custom policies", %{master: master, incoming: incoming} do
+    options = [policies: %{"9XX" => :append, "6XX" => :keep}]
+    %{"record" => record} = merge = MarcRecord.merge_records_wrapper(master, incoming, options)
+
+    assert field_data(record, "949") == [["HB171 .M37 2011", "Main stacks"], ["VENDOR-LOCAL"]]
+    assert field_data(record, "650") == [["Economic history."], ["Economics."]]
+    assert changes(merge, "650") == []
+    assert changes(merge, "949") == [{"added", "=949  \\\\$aVENDOR-LOCAL"}]
+  end
+
+  test "invalid policies are rejected", %{master: master, incoming: incoming} do
+    assert_raise ArgumentError, fn ->
+      MarcRecord.merge_records_wrapper(master, incoming, policies: %{"6" => :union})
+    end
+  end
+
+  test "convenience function" do
+    %{"changes" => changes} =
+      MarcRecord.merge_records("samples/merge_master.mrc", "samples/merge_vendor.mrc")
+
+    assert length(changes) == 8
+  end
+end
@@ -0,0 +1,76 @@
+defmodule MarcRecordModsTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, mods: MarcRecord.to_mods("samples/marc8_multiple.mrc")}
+  end
+
+  test "one MODS document per record", state do
+    assert length(state[:mods]) == 109
+    assert Enum.all?(state[:mods], &String.starts_with?(&1, "<?xml version=\"1.0\""))
+  end
+
+  test "record 1: title, names and type of resource", state do
+    mods = Enum.at(state[:mods], 0)
+
+    assert mods =~ "<title>Théories économiques en 30 secondes</title>"
+    assert mods =~ "<titleInfo type=\"alternative\">"
+    assert mods =~ "<name type=\"personal\" usage=\"primary\">"
+    assert mods =~ "<namePart>Marron, Donald B. (Baird)</namePart>"
+    assert mods =~ "<typeOfResource>text</typeOfResource>"
+    assert mods =~ "<genre authority=\"rvmgf\">Livres numériques</genre>"
+  end
+
+  test "record 1: originInfo comes from 260 and 008", state do
+    mods = Enum.at(state[:mods], 0)
+
+    assert mods =~ "<placeTerm type=\"code\" authority=\"marccountry\">quc</placeTerm>"
+    assert mods =~ "<placeTerm type=\"text\">Montréal</placeTerm>"
+    assert mods =~ "<publisher>Hurtubise</publisher>"
+    assert mods =~ "<dateIssued encoding=\"marc\" keyDate=\"yes\">2011</dateIssued>"
+    assert mods =~ "<issuance>monographic</issuance>"
+  end
+
+  test "record 41: the period of a final initial is kept", state do
+    mods = Enum.at(state[:mods], 40)
+
+    assert mods =~ "<namePart>Laflamme, Sonia K.</namePart>"
+    assert mods =~ "<namePart type=\"date\">1969-</namePart>"
+  end
+
+  test "record 1: subjects, classification and identifiers", state do
+    mods = Enum.at(state[:mods], 0)
+
+    assert mods =~ "<subject authority=\"rvm\">\n    <topic>Économie politique</topic>\n    <genre>Ouvrages de vulgarisation</genre>"
+    assert mods =~ "<classification authority=\"ddc\" edition=\"23\">330.1 M361t</classification>"
+    assert mods =~ "<identifier type=\"isbn\">9782896479764</identifier>"
+    assert mods =~ "<recordIdentifier>B301882</recordIdentifier>"
+  end
+
+  test "the type of 024 identifiers comes from the first indicator or $2" do
+    mods =
+      File.read!("samples/marc8_multiple.mrc")
+      |> :binary.replace("020001800100020002600118", "024001800100024002600118")
+      |> :binary.replace(<<"  ", 0x1F, "z9782896475285">>, <<"7 ", 0x1F, "2doi", 0x1F, "a10.123/x">>)
+      |> :binary.replace(<<"  ", 0x1F, "a9782896479764">>, <<"3 ", 0x1F, "a9782896479764">>)
+      |> MarcRecord.to_mods_wrapper()
+      |> hd()
+
+    assert mods =~ "<identifier type=\"doi\">10.123/x</identifier>"
+    assert mods =~ "<identifier type=\"ean\">9782896479764</identifier>"
+  end
+
+  test "nonfiling characters of uniform titles go to nonSort" do
+    mods =
+      File.read!("samples/marc8_multiple.mrc")
+      |> :binary.replace(<<"10", 0x1F, "aLemonade crime.">>, <<"14", 0x1F, "aThe Lemon crime">>)
+      |> :binary.replace(<<"0 ", 0x1F, "aChuck's day off">>, <<"4 ", 0x1F, "aThe Chuck's day">>)
+      |> MarcRecord.to_mods_wrapper()
+
+    assert Enum.at(mods, 27) =~
+             "<titleInfo type=\"uniform\">\n    <nonSort>The </nonSort>\n    <title>Lemon crime"
+
+    assert Enum.at(mods, 35) =~
+             "<titleInfo type=\"uniform\">\n    <nonSort>The </nonSort>\n    <title>Chuck&apos;s day"
+  end
+end
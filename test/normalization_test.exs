@@ -0,0 +1,48 @@
+defmodule MarcRecordNormalizationTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, data: File.read!("samples/marc8_multiple.mrc")}
+  end
+
+  defp title(data, options) do
+    data
+    |> MarcRecord.parse_records_wrapper(options)
+    |> hd()
+    |> Map.get("fields")
+    |> Enum.find(fn field -> field["tag"] == "245" end)
+    |> Map.get("subfields")
+    |> Enum.find_value(fn subfield -> if subfield["tag"] == "a", do: subfield["data"] end)
+  end
+
+  test "NFC composes the diacritics", state do
+    title = title(state[:data], normalization: :nfc)
+
+    assert title == "Théories économiques en 30 secondes :"
+    assert title == :unicode.characters_to_nfc_binary(title)
+  end
+
+  test "NFD decomposes the diacritics", state do
+    title = title(state[:data], normalization: :nfd)
+
+    assert title == "Théories économiques en 30 secondes :"
+  end
+
+  test "the search key has no diacritics", state do
+    assert title(state[:data], normalization: :search_key) ==
+             "Theories economiques en 30 secondes :"
+  end
+
+  test "the control fields are normalized too", state do
+    [record | _] = MarcRecord.parse_records_wrapper(state[:data], normalization: :nfd)
+
+    assert Enum.find(record["fields"], fn field -> field["tag"] == "001" end)["data"] ==
+             "B301882"
+  end
+
+  test "an unknown form is rejected", state do
+    assert_raise ArgumentError, fn ->
+      MarcRecord.parse_records_wrapper(state[:data], normalization: :nfkc)
+    end
+  end
+end
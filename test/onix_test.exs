@@ -0,0 +1,162 @@
+defmodule MarcRecordOnixTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, records: MarcRecord.onix_to_marc_wrapper(File.read!("samples/onix.xml"))}
+  end
+
+  defp fields(record, tag) do
+    Enum.filter(record["fields"], fn field -> field["tag"] == tag end)
+  end
+
+  defp field(record, tag), do: hd(fields(record, tag))
+
+  defp subfield(field, code) do
+    Enum.find_value(field["subfields"], fn subfield ->
+      if subfield["tag"] == code, do: subfield["data"]
+    end)
+  end
+
+  test "every product becomes a record", state do
+    assert length(state[:records]) == 2
+    assert field(hd(state[:records]), "001")["data"] == "com.example.9782896479764"
+  end
+
+  test "record 1: identifiers and title", state do
+    record = Enum.at(state[:records], 0)
+
+    assert subfield(field(record, "020"), "a") == "9782896479764"
+    assert subfield(field(record, "020"), "q") == "EPUB"
+    assert field(record, "245")["indicator"] == "14"
+    assert subfield(field(record, "245"), "a") == "Les théories économiques en 30 secondes"
+    assert subfield(field(record, "245"), "b") == "les 50 théories les plus stimulantes"
+  end
+
+  test "record 1: contributors are ordered by sequence number", state do
+    record = Enum.at(state[:records], 0)
+
+    assert subfield(field(record, "100"), "a") == "Marron, Donald B."
+    assert subfield(field(record, "100"), "4") == "aut"
+    assert subfield(field(record, "700"), "a") == "Fishwick, Adam"
+    assert subfield(field(record, "700"), "4") == "trl"
+  end
+
+  test "record 1: subjects and links", state do
+    record = Enum.at(state[:records], 0)
+
+    assert Enum.sort(Enum.map(fields(record, "650"), &subfield(&1, "a"))) == [
+             "BUSINESS & ECONOMICS / Economics / General",
+             "Economics"
+           ]
+
+    assert subfield(field(record, "655"), "a") == "Electronic books"
+    assert Enum.sort(Enum.map(fields(record, "653"), &subfield(&1, "a"))) == [
+             "vulgarisation",
+             "économie"
+           ]
+
+    assert Enum.sort(Enum.map(fields(record, "856"), &subfield(&1, "u"))) == [
+             "https://example.com/covers/9782896479764.jpg",
+             "https://example.com/livres/9782896479764"
+           ]
+  end
+
+  test "record 2: a corporate editor is an added entry", state do
+    record = Enum.at(state[:records], 1)
+
+    assert fields(record, "110") == []
+    assert subfield(field(record, "710"), "a") == "Example Society"
+    assert field(record, "245")["indicator"] == "00"
+  end
+
+  test "the author is the main entry even when another contributor comes first" do
+    [record] =
+      MarcRecord.onix_to_marc_wrapper("""
+      <ONIXMessage release="3.0">
+        <Product>
+          <RecordReference>com.example.1</RecordReference>
+          <DescriptiveDetail>
+            <ProductForm>BC</ProductForm>
+            <TitleDetail>
+              <TitleType>01</TitleType>
+              <TitleElement>
+                <TitleElementLevel>01</TitleElementLevel>
+                <TitleText>Collected essays</TitleText>
+              </TitleElement>
+            </TitleDetail>
+            <Contributor>
+              <SequenceNumber>1</SequenceNumber>
+              <ContributorRole>B01</ContributorRole>
+              <PersonNameInverted>Rosa, Guy</PersonNameInverted>
+            </Contributor>
+            <Contributor>
+              <SequenceNumber>2</SequenceNumber>
+              <ContributorRole>A01</ContributorRole>
+              <PersonNameInverted>Hugo, Victor</PersonNameInverted>
+            </Contributor>
+            <Contributor>
+              <SequenceNumber>3</SequenceNumber>
+              <ContributorRole>A23</ContributorRole>
+              <PersonNameInverted>Vargas Llosa, Mario</PersonNameInverted>
+            </Contributor>
+          </DescriptiveDetail>
+        </Product>
+      </ONIXMessage>
+      """)
+
+    assert subfield(field(record, "100"), "a") == "Hugo, Victor"
+    assert field(record, "245")["indicator"] == "10"
+
+    assert Enum.map(fields(record, "700"), &subfield(&1, "4")) == ["edt", "wfw"]
+  end
+
+  test "contributor roles map to MARC relators" do
+    roles = [
+      {"A01", "aut", "author"},
+      {"A06", "cmp", "composer"},
+      {"A08", "pht", "photographer"},
+      {"A12", "ill", "illustrator"},
+      {"A15", "wpr", "writer of preface"},
+      {"A23", "wfw", "writer of foreword"},
+      {"A24", "aui", "author of introduction"},
+      {"B01", "edt", "editor"},
+      {"B06", "trl", "translator"},
+      {"E07", "nrt", "narrator"}
+    ]
+
+    contributors =
+      roles
+      |> Enum.with_index(1)
+      |> Enum.map_join(fn {{role, _, _}, number} ->
+        """
+        <Contributor>
+          <SequenceNumber>#{number}</SequenceNumber>
+          <ContributorRole>#{role}</ContributorRole>
+          <PersonNameInverted>Name, #{number}</PersonNameInverted>
+        </Contributor>
+        """
+      end)
+
+    [record] =
+      MarcRecord.onix_to_marc_wrapper("""
+      <ONIXMessage release="3.0">
+        <Product>
+          <RecordReference>com.example.1</RecordReference>
+          <DescriptiveDetail>
+            <ProductForm>BC</ProductForm>
+            #{contributors}
+          </DescriptiveDetail>
+        </Product>
+      </ONIXMessage>
+      """)
+
+    entries = [field(record, "100") | fields(record, "700")]
+
+    assert Enum.map(entries, &{subfield(&1, "4"), subfield(&1, "e")}) ==
+             Enum.map(roles, fn {_, code, term} -> {code, term} end)
+  end
+
+  test "a document that is not an ONIX message is rejected" do
+    assert {:error, "Error in ONIX message: " <> _} = MarcRecord.onix_to_marc_wrapper("<html/>")
+  end
+end
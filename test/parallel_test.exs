@@ -0,0 +1,44 @@
+defmodule MarcRecordParallelTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, data: File.read!("samples/marc8_multiple.mrc")}
+  end
+
+  test "parallel parsing keeps the order of the records", %{data: data} do
+    assert MarcRecord.parse_records_wrapper(data, parallel: true) ==
+             MarcRecord.parse_records_wrapper(data)
+  end
+
+  test "parallel parsing combines with the other options", %{data: data} do
+    options = [normalization: :search_key, strip_punctuation: true]
+
+    assert MarcRecord.parse_records_wrapper(data, [{:parallel, true} | options]) ==
+             MarcRecord.parse_records_wrapper(data, options)
+  end
+
+  test "the first error is returned" do
+    data = File.read!("samples/more_difficult.mrc")
+
+    assert MarcRecord.parse_records_wrapper(data, parallel: true) ==
+             MarcRecord.parse_records_wrapper(data)
+
+    assert {:error, "Error in crate marc-record: " <> _} =
+             MarcRecord.parse_records_wrapper(data, parallel: true)
+  end
+
+  test "converters keep the order of the records", %{data: data} do
+    one_by_one =
+      data
+      |> MarcRecord.chunk_records_wrapper(1)
+      |> Enum.flat_map(&MarcRecord.to_dublin_core_wrapper/1)
+
+    assert MarcRecord.to_dublin_core_wrapper(data) == one_by_one
+    assert length(one_by_one) == 109
+
+    # Binaries of 1 MB or more are converted on all the cores.
+    large = String.duplicate(data, 4)
+    assert byte_size(large) >= 1_048_576
+    assert MarcRecord.to_dublin_core_wrapper(large) == Enum.concat(List.duplicate(one_by_one, 4))
+  end
+end
@@ -0,0 +1,51 @@
+defmodule MarcRecordPunctuationTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/marc8_multiple.mrc")
+    {:ok, records: MarcRecord.parse_records_wrapper(data, strip_punctuation: true)}
+  end
+
+  defp subfield(record, tag, code) do
+    record["fields"]
+    |> Enum.filter(fn field -> field["tag"] == tag end)
+    |> Enum.flat_map(fn field -> field["subfields"] end)
+    |> Enum.find_value(fn subfield -> if subfield["tag"] == code, do: subfield["data"] end)
+  end
+
+  test "the terminal ISBD punctuation is removed", state do
+    record = Enum.at(state[:records], 0)
+
+    assert subfield(record, "655", "a") == "Livres numériques"
+    assert subfield(record, "260", "a") == "Montréal"
+    assert subfield(record, "260", "b") == "Hurtubise"
+    assert subfield(record, "260", "c") == "2011"
+  end
+
+  test "the period of an initial is kept", state do
+    assert subfield(Enum.at(state[:records], 0), "100", "a") == "Marron, Donald B."
+  end
+
+  test "the period of an abbreviation in the extent is kept", state do
+    record = Enum.find(state[:records], fn record -> subfield(record, "300", "a") =~ "p.)" end)
+
+    assert String.ends_with?(subfield(record, "300", "a"), "p.)")
+  end
+
+  test "conversions remove the punctuation like the option", state do
+    summary = Enum.at(MarcRecord.summary_wrapper(File.read!("samples/marc8_multiple.mrc")), 11)
+
+    assert subfield(Enum.at(state[:records], 11), "650", "v") == "Romans, nouvelles, etc."
+    assert "Séquestration--Romans, nouvelles, etc." in summary["subjects"]
+  end
+
+  test "control subfields are left as is", state do
+    assert subfield(Enum.at(state[:records], 0), "655", "2") == "rvmgf"
+  end
+
+  test "the punctuation is kept by default" do
+    [record | _] = MarcRecord.parse_records_wrapper(File.read!("samples/marc8_multiple.mrc"))
+
+    assert subfield(record, "655", "a") == "Livres numériques."
+  end
+end
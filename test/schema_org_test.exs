@@ -0,0 +1,31 @@
+defmodule MarcRecordSchemaOrgTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, documents: MarcRecord.to_schema_org("samples/marc8_multiple.mrc")}
+  end
+
+  test "one JSON-LD document per record", state do
+    assert length(state[:documents]) == 109
+    assert Enum.all?(state[:documents], &(&1 =~ ~s("@context": "https://schema.org")))
+  end
+
+  test "record 1: e-book described as a Book", state do
+    document = Enum.at(state[:documents], 0)
+
+    assert document =~ ~s("@type": "Book")
+    assert document =~ ~s("name": "Théories économiques en 30 secondes : les 50 théories économiques les plus marquantes, expliquées en moins d'une minute")
+    assert document =~ ~s|"name": "Marron, Donald B. (Baird)"|
+    assert document =~ ~s("isbn": [\n    "9782896479764"\n  ])
+    assert document =~ ~s("datePublished": "2011")
+    assert document =~ ~s("inLanguage": "fr")
+    assert document =~ ~s("bookFormat": "https://schema.org/EBook")
+    assert document =~ ~s("numberOfPages": 159)
+  end
+
+  test "record 1: publisher is an Organization", state do
+    document = Enum.at(state[:documents], 0)
+
+    assert document =~ ~s("publisher": {\n    "@type": "Organization",\n    "name": "Hurtubise"\n  })
+  end
+end
@@ -0,0 +1,50 @@
+defmodule MarcRecordStandardNumberTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/standard_numbers.mrc")
+
+    {:ok,
+     numbers: MarcRecord.standard_numbers_wrapper(data),
+     summaries: MarcRecord.summary_wrapper(data)}
+  end
+
+  test "an ISBN-10 is converted to ISBN-13 and its qualifier separated", state do
+    isbn = Enum.at(Enum.at(state[:numbers], 0), 0)
+
+    assert isbn["type"] == "isbn"
+    assert isbn["raw"] == "2-924309-31-X (rel.)"
+    assert isbn["number"] == "9782924309315"
+    assert isbn["isbn10"] == "292430931X"
+    assert isbn["qualifiers"] == ["rel"]
+    assert isbn["valid"]
+    refute isbn["cancelled"]
+  end
+
+  test "a wrong check digit is flagged", state do
+    isbn = Enum.at(Enum.at(state[:numbers], 0), 1)
+
+    assert isbn["number"] == "9782764412340"
+    assert isbn["qualifiers"] == ["PDF"]
+    refute isbn["valid"]
+  end
+
+  test "numbers of $z are cancelled", state do
+    [isbn] = Enum.filter(Enum.at(state[:numbers], 0), &(&1["subfield"] == "z"))
+
+    assert isbn["valid"]
+    assert isbn["cancelled"]
+  end
+
+  test "ISSNs are checked", state do
+    assert Enum.map(Enum.at(state[:numbers], 1), &{&1["subfield"], &1["number"], &1["valid"]}) ==
+             [{"a", "0028-0836", true}, {"y", "00280837", false}, {"z", "12345678", false}]
+  end
+
+  test "the summary lists the valid numbers of $a", state do
+    [book, serial] = state[:summaries]
+
+    assert book["isbns"] == ["9782924309315", "9780198526636", "9791069000452"]
+    assert serial["issns"] == ["0028-0836"]
+  end
+end
@@ -0,0 +1,71 @@
+defmodule MarcRecordStatsTest do
+  use ExUnit.Case
+
+  defp put_byte(data, position, byte) do
+    binary_part(data, 0, position) <>
+      <<byte>> <> binary_part(data, position + 1, byte_size(data) - position - 1)
+  end
+
+  test "file statistics" do
+    stats = MarcRecord.stats("samples/marc8_multiple.mrc")
+
+    assert stats["record_count"] == 109
+    assert stats["invalid_count"] == 0
+    assert_in_delta stats["average_length"], 2893.35, 0.01
+    assert stats["record_types"] == %{"language_material" => 109}
+    assert stats["bibliographic_levels"] == %{"monograph" => 109}
+    assert stats["encoding_levels"] == %{"partial" => 40, "full_material_not_examined" => 69}
+    assert stats["coding_schemes"] == %{"marc8" => 109}
+  end
+
+  test "tag and subfield frequencies" do
+    stats = MarcRecord.stats("samples/marc8_multiple.mrc")
+
+    assert stats["tags"]["245"] == 109
+    assert stats["subfields"]["245"] == %{"a" => 109, "b" => 25, "c" => 109}
+    refute Map.has_key?(stats["subfields"], "001")
+  end
+
+  test "records which cannot be decoded are counted" do
+    stats = MarcRecord.stats("samples/more_difficult.mrc")
+
+    assert stats["record_count"] == 36
+    assert stats["invalid_count"] == 1
+    assert stats["tags"]["245"] == 35
+  end
+
+  test "authority records" do
+    stats = MarcRecord.stats("samples/authority.mrc")
+
+    assert stats["record_types"] == %{"authority" => 3}
+    assert stats["encoding_levels"] == %{"complete_authority" => 2, "incomplete_authority" => 1}
+  end
+
+  test "records with leader values undefined in their format are invalid" do
+    data = File.read!("samples/marc8_multiple.mrc")
+    second = String.to_integer(binary_part(data, 0, 5))
+
+    stats =
+      data
+      |> put_byte(6, ?q)
+      |> put_byte(second + 17, ?Q)
+      |> MarcRecord.stats_wrapper([])
+
+    assert stats["record_count"] == 109
+    assert stats["invalid_count"] == 2
+    assert stats["record_types"] == %{"language_material" => 107}
+  end
+
+  test "records with a corrupted directory are invalid" do
+    data = File.read!("samples/marc8_multiple.mrc")
+
+    stats =
+      data
+      |> put_byte(27, ?9)
+      |> MarcRecord.stats_wrapper([])
+
+    assert stats["record_count"] == 109
+    assert stats["invalid_count"] == 1
+    assert stats["record_types"] == %{"language_material" => 108}
+  end
+end
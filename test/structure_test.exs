@@ -0,0 +1,57 @@
+defmodule MarcRecordStructureTest do
+  use ExUnit.Case
+
+  setup_all do
+    records =
+      File.read!("samples/more_difficult.mrc")
+      |> MarcRecord.parse_records_with_warnings_wrapper()
+
+    {:ok, records: records}
+  end
+
+  test "return one entry per record even when a record cannot be decoded", state do
+    assert length(state[:records]) == 36
+  end
+
+  test "record 1: is parsed without warnings", state do
+    entry = Enum.at(state[:records], 0)
+    assert entry["warnings"] == []
+    assert entry["record"]["leader"]["record_length"] == 3894
+  end
+
+  test "record 29: decoding error is reported instead of failing the whole file", state do
+    entry = Enum.at(state[:records], 28)
+    assert entry["record"] == nil
+    assert [{"error", nil, nil, "Error in crate marc-record: " <> _}] = entry["warnings"]
+  end
+
+  test "record length disagreeing with the actual length is reported" do
+    <<_length::binary-size(5), rest::binary>> = File.read!("samples/marc8_multiple.mrc")
+
+    [entry | _] = MarcRecord.parse_records_with_warnings_wrapper(<<"01600", rest::binary>>)
+    message = "leader record length 1600 does not match actual length 1649"
+
+    assert {"warning", nil, nil, message} in entry["warnings"]
+  end
+
+  test "a directory entry pointing past the data is reported instead of failing the whole file" do
+    <<leader_and_tag::binary-size(27), _length::binary-size(4), rest::binary>> =
+      File.read!("samples/marc8_multiple.mrc")
+
+    [entry | others] =
+      MarcRecord.parse_records_with_warnings_wrapper(<<leader_and_tag::binary, "9999", rest::binary>>)
+
+    assert entry["record"] == nil
+    assert {"error", nil, nil, "Error in crate marc-record: " <> message} = List.last(entry["warnings"])
+    assert message =~ "field 001 (9999 bytes at 0) runs past the 1264 bytes of data"
+    assert length(others) == 108
+  end
+
+  test "truncated data is reported as a short record" do
+    [entry] = MarcRecord.parse_records_with_warnings_wrapper(<<"aa"::binary>>)
+    assert entry["record"] == nil
+    message = "record is 2 bytes long, shorter than the leader"
+
+    assert {"warning", nil, nil, message} in entry["warnings"]
+  end
+end
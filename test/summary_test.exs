@@ -0,0 +1,43 @@
+defmodule MarcRecordSummaryTest do
+  use ExUnit.Case
+
+  setup_all do
+    {:ok, summaries: MarcRecord.summary_wrapper(File.read!("samples/marc8_multiple.mrc"))}
+  end
+
+  test "there is a summary for every record", state do
+    assert length(state[:summaries]) == 109
+  end
+
+  test "record 1", state do
+    summary = Enum.at(state[:summaries], 0)
+
+    assert summary["title"] ==
+             "Théories économiques en 30 secondes : les 50 théories économiques les plus marquantes, expliquées en moins d'une minute"
+
+    assert summary["author"] == "Marron, Donald B. (Baird)"
+    assert summary["isbns"] == ["9782896479764"]
+    assert summary["issns"] == []
+    assert summary["year"] == 2011
+    assert summary["languages"] == ["fre"]
+    assert summary["subjects"] == ["Économie politique--Ouvrages de vulgarisation"]
+  end
+
+  test "the year of 260/264 $c comes before the year of 008" do
+    summary =
+      File.read!("samples/marc8_multiple.mrc")
+      |> :binary.replace("130125s2011", "130125s2010")
+      |> MarcRecord.summary_wrapper()
+      |> hd()
+
+    assert summary["year"] == 2011
+  end
+
+  test "record 4: the trailing punctuation of the title is removed", state do
+    summary = Enum.at(state[:summaries], 3)
+
+    assert summary["title"] == "Louis Riel : combattant métis"
+    assert summary["author"] == "Noël-Maw, Martine, 1961-"
+    assert "Riel, Louis, 1844-1885" in summary["subjects"]
+  end
+end
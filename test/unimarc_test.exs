@@ -0,0 +1,94 @@
+defmodule MarcRecordUnimarcTest do
+  use ExUnit.Case
+
+  setup_all do
+    data = File.read!("samples/unimarc.mrc")
+
+    {:ok,
+     records: MarcRecord.parse_records_wrapper(data, flavor: :unimarc),
+     unimarc: MarcRecord.unimarc_records_wrapper(data),
+     marc21: MarcRecord.unimarc_to_marc21_wrapper(data)}
+  end
+
+  defp field(record, tag) do
+    Enum.find(record["fields"], fn field -> field["tag"] == tag end)
+  end
+
+  defp subfield(field, code) do
+    Enum.find_value(field["subfields"], fn subfield ->
+      if subfield["tag"] == code, do: subfield["data"]
+    end)
+  end
+
+  test "unknown options are rejected" do
+    assert_raise ArgumentError, fn ->
+      MarcRecord.parse_records_wrapper(File.read!("samples/unimarc.mrc"), unknown: true)
+    end
+  end
+
+  test "the UNIMARC leader is decoded with its own values", state do
+    leaders = Enum.map(state[:records], fn record -> record["leader"] end)
+
+    assert Enum.map(leaders, fn leader -> leader["bibliographical_level"] end) == [
+             "monograph",
+             "serial"
+           ]
+
+    assert Enum.map(leaders, fn leader -> leader["hierarchical_level"] end) == [
+             "no_hierarchical_relationship",
+             "highest_level"
+           ]
+
+    assert Enum.map(leaders, fn leader -> leader["encoding_level"] end) == [
+             "partial",
+             "prepublication"
+           ]
+  end
+
+  test "record 1: title, authors and general processing data", state do
+    record = Enum.at(state[:unimarc], 0)
+
+    assert record["title"] == "Les misérables : roman / Victor Hugo"
+
+    assert record["authors"] == [
+             %{"tag" => "700", "name" => "Hugo, Victor (1802-1885)", "relator_code" => "070"},
+             %{"tag" => "702", "name" => "Rosa, Guy", "relator_code" => "340"}
+           ]
+
+    assert record["general_processing_data"]["date_entered_on_file"] == "20230115"
+    assert record["general_processing_data"]["type_of_publication_date"] == "monograph_single_date"
+    assert record["general_processing_data"]["language_of_cataloguing"] == "fre"
+  end
+
+  test "record 1: core fields are converted to MARC 21", state do
+    record = Enum.at(state[:marc21], 0)
+
+    assert field(record, "008")["data"] == "230115s1862    xx                  fre d"
+    assert subfield(field(record, "020"), "c") == "9,50 EUR"
+    assert subfield(field(record, "245"), "b") == "roman"
+    assert subfield(field(record, "100"), "a") == "Hugo, Victor"
+    assert subfield(field(record, "300"), "c") == "18 cm"
+    assert subfield(field(record, "041"), "a") == "fre"
+    assert subfield(field(record, "100"), "4") == "aut"
+    assert subfield(field(record, "700"), "4") == "edt"
+    assert subfield(field(record, "650"), "z") == "France"
+    assert field(record, "200") == nil
+  end
+
+  test "MARC 21 records are converted to UNIMARC" do
+    [record | _] = MarcRecord.marc21_to_unimarc("samples/marc8_multiple.mrc")
+
+    assert record["leader"]["hierarchical_level"] == "undefined"
+    assert field(record, "200") != nil
+    assert String.length(subfield(field(record, "100"), "a")) == 36
+    assert field(record, "245") == nil
+  end
+
+  test "UNIMARC records which are not UTF-8 are rejected with their character sets" do
+    data = String.replace(File.read!("samples/unimarc.mrc"), "é", <<0xC2, ?e>>, global: false)
+
+    {:error, message} = MarcRecord.parse_records_wrapper(data, flavor: :unimarc)
+    assert message =~ "UNIMARC record is not UTF-8"
+    assert message =~ "`50  `"
+  end
+end
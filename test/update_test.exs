@@ -0,0 +1,56 @@
+defmodule MarcRecordUpdateTest do
+  use ExUnit.Case
+
+  defp control_numbers(data) do
+    data
+    |> MarcRecord.parse_records_wrapper()
+    |> Enum.map(fn record ->
+      Enum.find_value(record["fields"], fn field -> field["tag"] == "001" && field["data"] end)
+    end)
+  end
+
+  test "updates keyed by control number" do
+    %{"data" => data, "summary" => summary} =
+      MarcRecord.apply_updates("samples/update_base.mrc", "samples/update_delta.mrc")
+
+    assert summary == %{
+             "replaced" => ["B-2"],
+             "deleted" => ["B-3"],
+             "added" => ["B-4"],
+             "not_found" => ["B-9"],
+             "without_key" => 1
+           }
+
+    assert control_numbers(data) == ["B-1", "B-2", "B-4"]
+  end
+
+  test "corrected records replace the base ones" do
+    %{"data" => data} =
+      MarcRecord.apply_updates("samples/update_base.mrc", "samples/update_delta.mrc")
+
+    [_, corrected, _] = MarcRecord.parse_records_wrapper(data)
+    title = Enum.find(corrected["fields"], fn field -> field["tag"] == "245" end)
+
+    assert hd(title["subfields"])["data"] == "Deuxième titre corrigé."
+    assert corrected["leader"]["status"] == "corrected"
+  end
+
+  test "updates keyed by system number" do
+    %{"data" => data, "summary" => summary} =
+      MarcRecord.apply_updates("samples/update_base.mrc", "samples/update_delta.mrc",
+        key: :system_number
+      )
+
+    assert summary["added"] == ["(OCoLC)104", "(OCoLC)110"]
+    assert summary["without_key"] == 0
+    assert control_numbers(data) == ["B-1", "B-2", "B-4", nil]
+  end
+
+  test "unknown key" do
+    base = File.read!("samples/update_base.mrc")
+
+    assert_raise ArgumentError, fn ->
+      MarcRecord.apply_updates_wrapper(base, base, key: :isbn)
+    end
+  end
+end
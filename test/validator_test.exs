@@ -0,0 +1,43 @@
+defmodule MarcRecordValidatorTest do
+  use ExUnit.Case
+
+  setup_all do
+    issues =
+      File.read!("samples/marc8_multiple.mrc")
+      |> MarcRecord.validate_records_wrapper()
+
+    {:ok, issues: issues}
+  end
+
+  test "validator return error for invalid data" do
+    content = <<"aa"::binary>>
+    {status, _} = MarcRecord.validate_records_wrapper(content)
+    assert status == :error
+  end
+
+  test "validator return one list of issues per record", state do
+    assert length(state[:issues]) == 109
+  end
+
+  test "record 1: has no issue", state do
+    assert Enum.at(state[:issues], 0) == []
+  end
+
+  test "issues are tuples of severity, tag, subfield and message", state do
+    issue =
+      state[:issues]
+      |> List.flatten()
+      |> Enum.find(fn {_, tag, subfield, _} -> tag == "082" and subfield == "d" end)
+
+    assert {"error", "082", "d", "subfield code `d` is not defined for field 082"} == issue
+  end
+
+  test "invalid indicators are reported", state do
+    messages =
+      state[:issues]
+      |> List.flatten()
+      |> Enum.map(fn {_, _, _, message} -> message end)
+
+    assert Enum.member?(messages, "invalid second indicator `5`")
+  end
+end
@@ -0,0 +1,46 @@
+defmodule MarcRecordZeroCopyTest do
+  use ExUnit.Case
+
+  defp subfield_values(records) do
+    for record <- records,
+        field <- record["fields"],
+        field["type"] == "data",
+        subfield <- field["subfields"],
+        do: subfield["data"]
+  end
+
+  test "the subfield data of UTF-8 records references the input" do
+    data = File.read!("samples/authority.mrc")
+    values = subfield_values(MarcRecord.parse_records_wrapper(data))
+
+    assert values != []
+
+    assert Enum.all?(values, fn value ->
+             :binary.referenced_byte_size(value) == byte_size(data)
+           end)
+  end
+
+  test "the subfield data is trimmed" do
+    data = File.read!("samples/merge_master.mrc")
+    values = subfield_values(MarcRecord.parse_records_wrapper(data))
+
+    assert "Théories économiques en trente secondes /" in values
+    assert Enum.all?(values, fn value -> value == String.trim(value) end)
+  end
+
+  test "MARC-8 records are transcoded into new binaries" do
+    data = File.read!("samples/marc8_multiple.mrc")
+    values = subfield_values(MarcRecord.parse_records_wrapper(data))
+
+    refute Enum.any?(values, fn value ->
+             :binary.referenced_byte_size(value) == byte_size(data)
+           end)
+  end
+
+  test "normalized data is not taken from the input" do
+    data = File.read!("samples/merge_master.mrc")
+    values = subfield_values(MarcRecord.parse_records_wrapper(data, normalization: :search_key))
+
+    assert "Theories economiques en trente secondes /" in values
+  end
+end